@@ -19,21 +19,35 @@
 // OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
+use std::ops::RangeInclusive;
+
 use itertools::Itertools;
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{
+    format_ident,
+    quote,
+};
 use syn::{
     parse2,
     parse_quote,
     Error,
     FnArg,
+    GenericArgument,
+    Ident,
     ItemTrait,
     Lit,
     Meta,
+    MetaNameValue,
     NestedMeta,
+    PathArguments,
+    PathSegment,
     ReturnType,
     TraitItem,
+    TraitItemConst,
     TraitItemMethod,
+    TraitItemType,
+    Type,
+    TypePath,
 };
 
 use crate::{
@@ -42,75 +56,378 @@ use crate::{
     utils::{
         into_u16,
         into_u32,
+        mentions_self,
+        replace_assoc_types,
         AttributeParser,
+        InputBindings,
+        LitOrPath,
+        MetaUtils,
     },
 };
 
+/// Method identifiers that are reserved for the capability handshake.
+///
+/// Keep in sync with `obce::codegen::RESERVED_FUNCTION_IDS`.
+const RESERVED_FUNCTION_IDS: RangeInclusive<u16> = 0xFFF0..=0xFFFF;
+
+/// Crates that provide primitive and prelude types.
+const STD_CRATES: &[&str] = &["core", "alloc", "std"];
+
+/// Primitive and prelude types, that are hashed by their names regardless of how they are spelled.
+const PRELUDE_TYPES: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128", "bool", "char", "str", "Option", "Result",
+    "Vec", "String", "Box",
+];
+
+/// Scheme that is used to derive identifiers that weren't specified explicitly.
+enum IdScheme {
+    /// Hash the bare trait and method names.
+    Name,
+    /// Hash the `crate::Trait` and `crate::Trait::method` paths, where `crate` is the name
+    /// of the crate that contains the definition.
+    Path(String),
+    /// Hash the `namespace::Trait` and `namespace::method` paths.
+    Namespace(String),
+    /// Require all identifiers to be specified explicitly.
+    Explicit,
+}
+
+impl IdScheme {
+    fn new<'a, I: IntoIterator<Item = &'a NestedMeta> + Clone>(iter: I) -> Result<Self, Error> {
+        let namespace = match iter.clone().find_by_name("namespace") {
+            Some((LitOrPath::Lit(Lit::Str(namespace)), _)) => Some(namespace),
+            Some((_, ident)) => return Err(format_err_spanned!(ident, "namespace should be a string")),
+            None => None,
+        };
+
+        let scheme = match iter.find_by_name("id_scheme") {
+            Some((LitOrPath::Lit(Lit::Str(scheme)), _)) => scheme,
+            Some((_, ident)) => return Err(format_err_spanned!(ident, "id_scheme should be a string")),
+            None => {
+                return match namespace {
+                    Some(namespace) => {
+                        Err(format_err_spanned!(
+                            namespace,
+                            "namespace can only be used with `namespace` identifier scheme"
+                        ))
+                    }
+                    None => Ok(Self::Name),
+                }
+            }
+        };
+
+        match (scheme.value().as_str(), namespace) {
+            ("namespace", Some(namespace)) => Ok(Self::Namespace(namespace.value())),
+            ("namespace", None) => {
+                Err(format_err_spanned!(
+                    scheme,
+                    "`namespace` identifier scheme requires a namespace to be specified"
+                ))
+            }
+            (_, Some(namespace)) => {
+                Err(format_err_spanned!(
+                    namespace,
+                    "namespace can only be used with `namespace` identifier scheme"
+                ))
+            }
+            ("name", None) => Ok(Self::Name),
+            ("path", None) => {
+                // Module paths are not available to procedural macros, so the crate name
+                // provided by Cargo is used to qualify the trait path.
+                std::env::var("CARGO_CRATE_NAME").map(Self::Path).map_err(|_| {
+                    format_err_spanned!(
+                        scheme,
+                        "`path` identifier scheme requires the crate to be built with Cargo"
+                    )
+                })
+            }
+            ("explicit", None) => Ok(Self::Explicit),
+            _ => {
+                Err(format_err_spanned!(
+                    scheme,
+                    "unknown identifier scheme, expected one of `name`, `path`, `namespace` or `explicit`"
+                ))
+            }
+        }
+    }
+
+    fn extension_id(&self, trait_name: &Ident) -> Option<u16> {
+        match self {
+            Self::Name => Some(into_u16(trait_name)),
+            Self::Path(krate) => Some(into_u16(format!("{krate}::{trait_name}"))),
+            Self::Namespace(namespace) => Some(into_u16(format!("{namespace}::{trait_name}"))),
+            Self::Explicit => None,
+        }
+    }
+
+    fn method_id(&self, trait_name: &Ident, method_name: &Ident) -> Option<u16> {
+        match self {
+            Self::Name => Some(into_u16(method_name)),
+            Self::Path(krate) => Some(into_u16(format!("{krate}::{trait_name}::{method_name}"))),
+            Self::Namespace(namespace) => Some(into_u16(format!("{namespace}::{method_name}"))),
+            Self::Explicit => None,
+        }
+    }
+
+    fn to_tokens(&self) -> TokenStream {
+        match self {
+            Self::Name => quote! { ::obce::codegen::IdScheme::Name },
+            Self::Path(krate) => quote! { ::obce::codegen::IdScheme::Path(#krate) },
+            Self::Namespace(namespace) => quote! { ::obce::codegen::IdScheme::Namespace(#namespace) },
+            Self::Explicit => quote! { ::obce::codegen::IdScheme::Explicit },
+        }
+    }
+}
+
 struct TraitAttrs {
+    name: Ident,
     id: u16,
+    id_scheme: IdScheme,
+    version: u16,
+    strict_abi: bool,
+    proptest: bool,
 }
 
 impl TraitAttrs {
-    fn new<'a, I: IntoIterator<Item = &'a NestedMeta>>(trait_item: &ItemTrait, iter: I) -> Result<Self, Error> {
-        let id = find_id(iter)?.unwrap_or_else(|| into_u16(&trait_item.ident));
+    fn new<'a, I: IntoIterator<Item = &'a NestedMeta> + Clone>(trait_item: &ItemTrait, iter: I) -> Result<Self, Error> {
+        let id_scheme = IdScheme::new(iter.clone())?;
 
-        Ok(Self { id })
+        let id = match find_id(iter.clone())? {
+            Some(id) => id,
+            None => {
+                id_scheme.extension_id(&trait_item.ident).ok_or_else(|| {
+                    format_err_spanned!(
+                        trait_item.ident,
+                        "chain extension identifier should be specified explicitly with `explicit` identifier scheme"
+                    )
+                })?
+            }
+        };
+
+        let version = find_version(iter.clone(), "version")?.unwrap_or(1);
+
+        let strict_abi = match iter.clone().find_by_name("strict_abi") {
+            Some((LitOrPath::Path, ident)) if matches!(id_scheme, IdScheme::Explicit) => {
+                return Err(format_err_spanned!(
+                    ident,
+                    "`strict_abi` can't be used with `explicit` identifier scheme, since method identifiers are derived from method signatures"
+                ))
+            }
+            Some((LitOrPath::Path, _)) => true,
+            _ => false,
+        };
+
+        let proptest = matches!(iter.find_by_name("proptest"), Some((LitOrPath::Path, _)));
+
+        if proptest && !trait_item.generics.params.is_empty() {
+            return Err(format_err_spanned!(
+                trait_item.generics,
+                "round-trip tests can't be generated for generic trait definitions"
+            ))
+        }
+
+        Ok(Self {
+            name: trait_item.ident.clone(),
+            id,
+            id_scheme,
+            version,
+            strict_abi,
+            proptest,
+        })
+    }
+    /// Describe how a conflicting method identifier can be changed.
+    fn different_id_hint(&self) -> &'static str {
+        if self.strict_abi {
+            "changing the method identifier salt with `#[obce(salt = ...)]`"
+        } else {
+            "specifying a different identifier"
+        }
     }
 }
 
 struct Method {
+    name: Ident,
+    uses_assoc_types: bool,
     id: u16,
+    alias_ids: Vec<u16>,
     hash: u32,
+    signature_hash: u32,
+    since: u16,
+    ret_val: bool,
+    deprecated: Option<TokenStream>,
     input_tokens: TokenStream,
     output_tokens: TokenStream,
+    description_input_tokens: TokenStream,
+    description_output_tokens: TokenStream,
+    roundtrip_test: Option<TokenStream>,
 }
 
 impl Method {
-    fn new(method_item: &mut TraitItemMethod) -> Result<Self, Error> {
-        if let Some(default) = &method_item.default {
+    fn new(trait_attrs: &TraitAttrs, method_item: &mut TraitItemMethod) -> Result<Self, Error> {
+        let (obce_attrs, other_attrs) = method_item.attrs.iter().cloned().split_attrs()?;
+
+        method_item.attrs = other_attrs;
+
+        let explicit_id = find_id(obce_attrs.iter())?;
+
+        // Explicit identifiers are never rewritten, so they can't reflect the method signature.
+        if trait_attrs.strict_abi && explicit_id.is_some() {
             return Err(format_err_spanned!(
-                default,
-                "default implementation is not supported in chain extensions"
+                method_item.sig.ident,
+                "explicit method identifiers can't be used with `strict_abi`, since method identifiers are derived from method signatures"
             ))
         }
 
-        let (obce_attrs, other_attrs) = method_item.attrs.iter().cloned().split_attrs()?;
-
-        method_item.attrs = other_attrs;
+        let mut id = match explicit_id {
+            Some(id) => id,
+            None => {
+                trait_attrs
+                    .id_scheme
+                    .method_id(&trait_attrs.name, &method_item.sig.ident)
+                    .ok_or_else(|| {
+                        format_err_spanned!(
+                            method_item.sig.ident,
+                            "method identifier should be specified explicitly with `explicit` identifier scheme"
+                        )
+                    })?
+            }
+        };
 
-        let id = find_id(obce_attrs.iter())
-            .transpose()
-            .unwrap_or_else(|| Ok(into_u16(&method_item.sig.ident)))?;
+        let alias_ids = find_alias_ids(obce_attrs.iter())?;
 
         let hash = into_u32(&method_item.sig.ident);
 
-        let input_tys = method_item.sig.inputs.iter().filter_map(|input| {
-            if let FnArg::Typed(pat) = input {
-                Some(&*pat.ty)
-            } else {
-                None
+        let ret_val = matches!(obce_attrs.iter().find_by_name("ret_val"), Some((LitOrPath::Path, _)));
+
+        let since = find_version(obce_attrs.iter(), "since")?;
+
+        if let Some(since) = since {
+            if since > trait_attrs.version {
+                return Err(format_err_spanned!(
+                    method_item.sig.ident,
+                    "method is available since version {since}, which is newer than the chain extension version {}",
+                    trait_attrs.version
+                ))
             }
-        });
 
-        let output_tokens = if let ReturnType::Type(_, ty) = &method_item.sig.output {
-            quote!(#ty)
+            let since_doc = format!("Available since version {since} of the chain extension.");
+
+            method_item.attrs.push(parse_quote! {
+                #[doc = #since_doc]
+            });
+        }
+
+        let deprecated = match obce_attrs.iter().find_by_name("deprecated") {
+            Some((LitOrPath::Path, _)) => Some(quote! { #[deprecated] }),
+            Some((LitOrPath::Lit(Lit::Str(note)), _)) => Some(quote! { #[deprecated(note = #note)] }),
+            Some((LitOrPath::Lit(lit), _)) => {
+                return Err(format_err_spanned!(lit, "deprecation note should be a string"))
+            }
+            None => None,
+        };
+
+        let input_tys: Vec<_> = method_item
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|input| {
+                if let FnArg::Typed(pat) = input {
+                    Some(&*pat.ty)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let output_ty = if let ReturnType::Type(_, ty) = &method_item.sig.output {
+            Some(&**ty)
+        } else {
+            None
+        };
+
+        let signature_hash = signature_hash(&input_tys, output_ty);
+
+        let salt = find_version(obce_attrs.iter(), "salt")?;
+
+        if let (Some(_), false) = (salt, trait_attrs.strict_abi) {
+            return Err(format_err_spanned!(
+                method_item.sig.ident,
+                "method identifier salt can only be used with `strict_abi`, consider specifying the identifier explicitly"
+            ))
+        }
+
+        // In strict ABI mode contracts that were built using a different method signature
+        // call a non-existent method instead of silently misinterpreting the data.
+        //
+        // Only 16 bits of the signature hash fit into the identifier, so derived identifiers
+        // may end up in the reserved range or collide with other methods, in which case
+        // the salt can be changed to derive a different identifier.
+        if trait_attrs.strict_abi {
+            id ^= signature_hash as u16 ^ salt.unwrap_or_default();
+        }
+
+        if let Some(id) = std::iter::once(&id)
+            .chain(&alias_ids)
+            .find(|id| RESERVED_FUNCTION_IDS.contains(id))
+        {
+            return Err(format_err_spanned!(
+                method_item.sig.ident,
+                "method identifier {id} is reserved for the capability handshake, consider {}",
+                trait_attrs.different_id_hint()
+            ))
+        }
+
+        let roundtrip_test = if trait_attrs.proptest {
+            Some(roundtrip_test(method_item, &input_tys, output_ty)?)
         } else {
-            quote!(())
+            None
         };
 
+        let uses_assoc_types = input_tys.iter().copied().chain(output_ty).any(mentions_self);
+
+        // Associated types are not available on trait objects, so only the methods
+        // that use them are excluded from the trait object.
+        if uses_assoc_types {
+            method_item
+                .sig
+                .generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote! { Self: Sized });
+        }
+
+        let description_input_tys = input_tys.iter().map(|ty| description_type(ty));
+        let description_output_ty = output_ty.map(description_type);
+
         Ok(Self {
+            name: method_item.sig.ident.clone(),
+            uses_assoc_types,
             id,
+            alias_ids,
             hash,
+            signature_hash,
+            // Methods without `since` attribute are available since the first version.
+            since: since.unwrap_or(1),
+            ret_val,
+            deprecated,
             input_tokens: quote! {
                 (#(#input_tys),*)
             },
-            output_tokens,
+            output_tokens: output_ty.map_or_else(|| quote!(()), |ty| quote!(#ty)),
+            description_input_tokens: quote! {
+                (#(#description_input_tys),*)
+            },
+            description_output_tokens: description_output_ty.map_or_else(|| quote!(()), |ty| quote!(#ty)),
+            roundtrip_test,
         })
     }
 
-    fn fill_with_ink_data(&self, trait_attrs: &TraitAttrs, method_item: &mut TraitItemMethod) {
+    /// Fill the method with ink! glue code, returning its non-trapping `try_*` counterpart.
+    fn fill_with_ink_data(&self, trait_attrs: &TraitAttrs, method_item: &mut TraitItemMethod) -> TraitItemMethod {
         let Method {
             id,
+            ret_val,
+            deprecated,
             input_tokens,
             output_tokens,
             ..
@@ -133,25 +450,67 @@ impl Method {
             });
         }
 
-        let input_bindings = method_item.sig.inputs.iter().filter_map(|input| {
-            if let FnArg::Typed(pat) = input {
-                Some(&*pat.pat)
-            } else {
-                None
-            }
-        });
+        let input_bindings: Vec<_> = method_item
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|input| {
+                if let FnArg::Typed(pat) = input {
+                    Some(&*pat.pat)
+                } else {
+                    None
+                }
+            })
+            .collect();
 
         // https://paritytech.github.io/substrate/master/pallet_contracts/chain_extension/trait.RegisteredChainExtension.html
         let trait_id = (trait_attrs.id as u32) << 16;
         let id_for_call = trait_id | (*id as u32);
 
+        // Non-zero status codes are produced by `RetVal`-convertible error variants
+        // on the Substrate side, so we map them back into the same variants here.
+        let call = if *ret_val {
+            quote! { ::obce::ink_lang::try_call_with_ret_val::<#input_tokens, #output_tokens> }
+        } else {
+            quote! { ::obce::ink_lang::try_call::<#input_tokens, #output_tokens> }
+        };
+
+        let method_name = &method_item.sig.ident;
+        let try_method_name = format_ident!("try_{}", method_name);
+        let try_method_doc = format!(
+            "Non-trapping version of [`{method_name}`](Self::{method_name}).\n\n\
+             Calls still trap if the method is unknown to a chain extension that is implemented \
+             with `#[obce::implementation(trap_unknown_functions)]` or with an older OBCE version."
+        );
+
+        let mut try_method_item = method_item.clone();
+        try_method_item.attrs.retain(|attr| !attr.path.is_ident("doc"));
+        try_method_item.attrs.push(parse_quote! {
+            #[doc = #try_method_doc]
+        });
+        try_method_item.sig.ident = try_method_name.clone();
+        try_method_item.sig.output = parse_quote! {
+            -> ::core::result::Result<#output_tokens, ::obce::ink_lang::CallError>
+        };
+        try_method_item.default = Some(parse_quote! {{
+            #call(#id_for_call, &(#(#input_bindings),*))
+        }});
+
+        // Deprecated methods still use their non-trapping versions internally.
+        if let Some(deprecated) = deprecated {
+            try_method_item.attrs.push(parse_quote! { #deprecated });
+            method_item.attrs.push(parse_quote! { #deprecated });
+            method_item.attrs.push(parse_quote! { #[allow(deprecated)] });
+        }
+
+        let receiver = method_item.sig.receiver().map(|_| quote! { self, });
+
         method_item.default = Some(parse_quote! {{
-            ::obce::ink_lang::env::chain_extension::ChainExtensionMethod::build(#id_for_call)
-                .input::<#input_tokens>()
-                .output::<#output_tokens, false>()
-                .ignore_error_code()
-                .call(&(#(#input_bindings),*))
+            Self::#try_method_name(#receiver #(#input_bindings),*)
+                .expect("chain extension method call failed")
         }});
+
+        try_method_item
     }
 }
 
@@ -161,45 +520,179 @@ pub fn generate(attrs: TokenStream, input: TokenStream) -> Result<TokenStream, E
     let trait_attrs = TraitAttrs::new(&trait_item, parse2::<AttributeArgs>(attrs)?.iter())?;
 
     let trait_id = trait_attrs.id;
+    let trait_version = trait_attrs.version;
+    let trait_id_scheme = trait_attrs.id_scheme.to_tokens();
+    let trait_strict_abi = trait_attrs.strict_abi;
     let trait_name = &trait_item.ident;
 
     let (impls, types, where_clause) = trait_item.generics.split_for_impl();
 
+    // Associated constants are shared by both sides of a chain extension.
+    // They are moved out of the trait to keep it usable as a trait object.
+    let mut constants = vec![];
+
+    trait_item.items.retain(|item| {
+        if let TraitItem::Const(const_item) = item {
+            constants.push(const_item.clone());
+            false
+        } else {
+            true
+        }
+    });
+
+    let constants: Vec<_> = constants
+        .into_iter()
+        .map(|const_item| {
+            let TraitItemConst {
+                attrs,
+                ident,
+                ty,
+                default,
+                ..
+            } = &const_item;
+
+            let Some((_, expr)) = default else {
+                return Err(format_err_spanned!(
+                    const_item,
+                    "associated constants should have a value, since it is shared by both sides of a chain extension"
+                ))
+            };
+
+            let vis = &trait_item.vis;
+
+            Ok(quote! {
+                #(#attrs)*
+                #vis const #ident: #ty = #expr;
+            })
+        })
+        .try_collect()?;
+
+    // Associated types are resolved separately by each side of a chain extension,
+    // so they are excluded from the trait object to keep it usable as a chain extension description.
+    for item in trait_item.items.iter_mut() {
+        if let TraitItem::Type(type_item) = item {
+            prepare_assoc_type(type_item)?;
+        }
+    }
+
+    // Methods with default implementations are helpers that are available only on the ink! side.
+    // They have no identifiers of their own and are built out of other chain extension calls.
     let methods: Vec<_> = trait_item
         .items
         .iter_mut()
-        .map(|item| {
-            if let TraitItem::Method(method) = item {
-                Method::new(method)
-            } else {
-                Err(format_err_spanned!(
-                    item,
-                    "only methods are supported in trait definitions"
-                ))
+        .filter_map(|item| {
+            match item {
+                TraitItem::Method(method) if method.default.is_some() => verify_helper_method(method).err().map(Err),
+                TraitItem::Method(method) => Some(Method::new(&trait_attrs, method)),
+                TraitItem::Type(_) => None,
+                _ => {
+                    Some(Err(format_err_spanned!(
+                        item,
+                        "only methods, associated types and associated constants are supported in trait definitions"
+                    )))
+                }
             }
         })
         .try_collect()?;
 
-    if let Some(id) = methods.iter().map(|Method { id, .. }| id).duplicates().next() {
+    let extension_method_names: Vec<_> = trait_item
+        .items
+        .iter()
+        .filter_map(|item| {
+            match item {
+                TraitItem::Method(method_item) if method_item.default.is_none() => Some(&method_item.sig.ident),
+                _ => None,
+            }
+        })
+        .collect();
+
+    let conflicting_name = trait_item.items.iter().find_map(|item| {
+        let TraitItem::Method(method_item) = item else {
+            return None
+        };
+
+        let name = &method_item.sig.ident;
+        let original_name = name.to_string().strip_prefix("try_")?.to_owned();
+
+        extension_method_names
+            .iter()
+            .any(|name| *name == &original_name)
+            .then_some((name, original_name))
+    });
+
+    if let Some((conflicting_name, original_name)) = conflicting_name {
         return Err(format_err_spanned!(
-            trait_item,
-            "found duplicated method identifier: {id}",
+            conflicting_name,
+            "`{conflicting_name}` conflicts with the generated non-trapping version of `{original_name}`",
         ))
     }
 
+    // Aliases share the identifier space with regular method identifiers,
+    // since they are dispatched in the same way.
+    if let Some(id) = methods
+        .iter()
+        .flat_map(|Method { id, alias_ids, .. }| std::iter::once(id).chain(alias_ids))
+        .duplicates()
+        .next()
+    {
+        return Err(if trait_attrs.strict_abi {
+            format_err_spanned!(
+                trait_item,
+                "found duplicated method identifier: {id}, consider {}",
+                trait_attrs.different_id_hint()
+            )
+        } else {
+            format_err_spanned!(trait_item, "found duplicated method identifier: {id}")
+        })
+    }
+
+    let abi_hash = into_u32(
+        methods
+            .iter()
+            .sorted_by_key(|Method { id, .. }| id)
+            .map(
+                |Method {
+                     id,
+                     alias_ids,
+                     signature_hash,
+                     ..
+                 }| {
+                    let aliases = alias_ids
+                        .iter()
+                        .sorted()
+                        .map(|alias_id| format!("|{alias_id}"))
+                        .join("");
+                    format!("{id}{aliases}:{signature_hash}")
+                },
+            )
+            .join(","),
+    );
+
+    let method_ids = methods
+        .iter()
+        .flat_map(|Method { id, alias_ids, .. }| std::iter::once(id).chain(alias_ids));
+
     let method_descriptions = methods.iter().map(
         |Method {
              id,
+             alias_ids,
              hash,
-             input_tokens,
-             output_tokens,
+             signature_hash,
+             since,
+             ret_val,
+             description_input_tokens,
+             description_output_tokens,
              ..
          }| {
             quote! {
                 impl #impls ::obce::codegen::MethodDescription<#hash> for dyn #trait_name #types #where_clause {
                     const ID: ::core::primitive::u16 = #id;
-                    type Input = #input_tokens;
-                    type Output = #output_tokens;
+                    const ALIAS_IDS: &'static [::core::primitive::u16] = &[#(#alias_ids),*];
+                    const SIGNATURE_HASH: ::core::primitive::u32 = #signature_hash;
+                    const SINCE: ::core::primitive::u16 = #since;
+                    const RET_VAL: ::core::primitive::bool = #ret_val;
+                    type Input = #description_input_tokens;
+                    type Output = #description_output_tokens;
                 }
             }
         },
@@ -207,45 +700,412 @@ pub fn generate(attrs: TokenStream, input: TokenStream) -> Result<TokenStream, E
 
     let mut ink_trait_item = trait_item.clone();
 
-    ink_trait_item
+    let try_method_items: Vec<_> = ink_trait_item
         .items
         .iter_mut()
+        .filter_map(|item| {
+            match item {
+                TraitItem::Method(method_item) if method_item.default.is_none() => Some(method_item),
+                _ => None,
+            }
+        })
         .zip(methods.iter())
-        .for_each(|(item, method)| {
-            if let TraitItem::Method(method_item) = item {
-                method.fill_with_ink_data(&trait_attrs, method_item);
-            } else {
-                // This branch is unreachable, because `ink_trait_item`
-                // is cloned from the `trait_item`, items of which are verified
-                // to be method above.
-                unreachable!("only methods are present here")
+        .map(|(method_item, method)| TraitItem::Method(method.fill_with_ink_data(&trait_attrs, method_item)))
+        .collect();
+
+    ink_trait_item.items.extend(try_method_items);
+
+    let roundtrip_tests = trait_attrs.proptest.then(|| {
+        let mod_name = format_ident!("__obce_roundtrip_{}", trait_name);
+        let tests = methods
+            .iter()
+            .filter_map(|Method { roundtrip_test, .. }| roundtrip_test.as_ref());
+
+        quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod #mod_name {
+                use super::*;
+
+                ::proptest::proptest! {
+                    #(#tests)*
+                }
             }
-        });
+        }
+    });
+
+    // Helper methods are not present on the Substrate side, since they
+    // are built out of chain extension calls.
+    trait_item
+        .items
+        .retain(|item| !matches!(item, TraitItem::Method(method_item) if method_item.default.is_some()));
+
+    let expectation_mock = expectation_mock(&trait_item, &methods);
 
     Ok(quote! {
         impl #impls ::obce::codegen::ExtensionDescription for dyn #trait_name #types #where_clause {
             const ID: ::core::primitive::u16 = #trait_id;
+            const VERSION: ::core::primitive::u16 = #trait_version;
+            const ID_SCHEME: ::obce::codegen::IdScheme = #trait_id_scheme;
+            const METHOD_IDS: &'static [::core::primitive::u16] = &[#(#method_ids),*];
+            const STRICT_ABI: ::core::primitive::bool = #trait_strict_abi;
+            const ABI_HASH: ::core::primitive::u32 = #abi_hash;
         }
 
         #(#method_descriptions)*
 
+        impl #impls dyn #trait_name #types #where_clause {
+            #(#constants)*
+        }
+
         #[cfg(feature = "substrate")]
         #trait_item
 
         #[cfg(feature = "ink")]
         #ink_trait_item
+
+        #roundtrip_tests
+
+        #expectation_mock
     })
 }
 
+/// Generate an expectation-style mock of the chain extension for off-chain ink! testing.
+///
+/// Methods that use associated types are not present in the mock, since their
+/// input and output types can't be resolved without the ink! side of a chain extension.
+fn expectation_mock(trait_item: &ItemTrait, methods: &[Method]) -> TokenStream {
+    let trait_name = &trait_item.ident;
+    let vis = &trait_item.vis;
+    let mock_name = format_ident!("Mock{}", trait_name);
+    let mut mock_doc = format!("Expectation-style mock of [`{trait_name}`] chain extension.");
+
+    // Generic definitions have no concrete input and output types to mock.
+    if !trait_item.generics.params.is_empty() {
+        return TokenStream::new()
+    }
+
+    let (methods, skipped_methods): (Vec<_>, Vec<_>) = methods.iter().partition(|method| !method.uses_assoc_types);
+
+    if !skipped_methods.is_empty() {
+        let skipped_names = skipped_methods
+            .iter()
+            .map(|Method { name, .. }| format!("[`{name}`]({trait_name}::{name})"))
+            .join(", ");
+
+        mock_doc.push_str(&format!(
+            "\n\nThe following methods use associated types, and thus are not mocked: {skipped_names}. \
+             Use `#[obce::mock]` to mock them instead."
+        ));
+    }
+
+    let method_names: Vec<_> = methods.iter().map(|Method { name, .. }| name).collect();
+
+    let io_tys: Vec<_> = methods
+        .iter()
+        .map(|Method { hash, .. }| {
+            quote! {
+                <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::Input,
+                <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::Output
+            }
+        })
+        .collect();
+
+    let expect_methods = methods.iter().zip(&io_tys).map(|(Method { name, .. }, io_tys)| {
+        let expect_name = format_ident!("expect_{}", name);
+        let expect_doc = format!("Add an expectation of [`{name}`]({trait_name}::{name}) method call.");
+
+        quote! {
+            #[doc = #expect_doc]
+            pub fn #expect_name(&self) -> ::obce::ink_lang::mock::Expectation<#io_tys> {
+                self.#name.expect()
+            }
+        }
+    });
+
+    let method_ids: Vec<_> = methods
+        .iter()
+        .map(|Method { hash, .. }| {
+            quote! {
+                <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::ID
+            }
+        })
+        .collect();
+
+    quote! {
+        #[cfg(all(feature = "ink", any(test, feature = "std")))]
+        #[doc = #mock_doc]
+        #vis struct #mock_name {
+            #(#method_names: ::obce::ink_lang::mock::Expectations<#io_tys>,)*
+        }
+
+        #[cfg(all(feature = "ink", any(test, feature = "std")))]
+        impl #mock_name {
+            /// Create a new mock without any expectations.
+            pub fn new() -> Self {
+                Self {
+                    #(#method_names: ::obce::ink_lang::mock::Expectations::new(::core::stringify!(#method_names)),)*
+                }
+            }
+
+            #(#expect_methods)*
+
+            /// Register the mock in the ink! off-chain environment.
+            pub fn register(&self) {
+                let trait_id = (<dyn #trait_name as ::obce::codegen::ExtensionDescription>::ID as u32) << 16;
+                #(self.#method_names.register(trait_id | (#method_ids as u32));)*
+                ::obce::ink_lang::mock::register_capabilities::<dyn #trait_name>(&[#(#method_ids),*]);
+            }
+        }
+
+        #[cfg(all(feature = "ink", any(test, feature = "std")))]
+        impl ::core::default::Default for #mock_name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #[cfg(all(feature = "ink", any(test, feature = "std")))]
+        impl ::core::ops::Drop for #mock_name {
+            fn drop(&mut self) {
+                #(self.#method_names.verify();)*
+            }
+        }
+    }
+}
+
+/// Generate a `proptest` test, that checks whether method input and output survive a round-trip
+/// between ink! encoding and Substrate decoding.
+///
+/// The input is encoded as a tuple of method arguments (as ink! glue code does), decoded into
+/// the same pattern that is used by the Substrate dispatcher and compared with the original arguments.
+fn roundtrip_test(
+    method_item: &TraitItemMethod,
+    input_tys: &[&Type],
+    output_ty: Option<&Type>,
+) -> Result<TokenStream, Error> {
+    if let Some(ty) = input_tys.iter().copied().chain(output_ty).find(|ty| mentions_self(ty)) {
+        return Err(format_err_spanned!(
+            ty,
+            "round-trip tests can't be generated for methods that use associated types"
+        ))
+    }
+
+    let method_name = &method_item.sig.ident;
+    let input_bindings = InputBindings::from_iter(&method_item.sig.inputs);
+    let lhs_pat = input_bindings.lhs_pat(None);
+    let call_params = input_bindings.iter_call_params();
+
+    let inputs: Vec<_> = (0..input_tys.len())
+        .map(|n| format_ident!("__obce_input_{}", n))
+        .collect();
+    let input_tokens = quote! { (#(#input_tys),*) };
+    let output_tokens = output_ty.map_or_else(|| quote!(()), |ty| quote!(#ty));
+
+    Ok(quote! {
+        #[test]
+        fn #method_name(
+            #(#inputs in ::proptest::prelude::any::<#input_tys>(),)*
+            __obce_output in ::proptest::prelude::any::<#output_tokens>()
+        ) {
+            let encoded = <#input_tokens as ::scale::Encode>::encode(&(#(::core::clone::Clone::clone(&#inputs)),*));
+            let #lhs_pat = ::obce::codegen::decode_roundtrip::<#input_tokens>(&encoded)
+                .map_err(|_| ::proptest::test_runner::TestCaseError::fail("unable to decode method input"))?;
+            #(::proptest::prop_assert_eq!(&#call_params, &#inputs);)*
+
+            let encoded = <#output_tokens as ::scale::Encode>::encode(&__obce_output);
+            let decoded = ::obce::codegen::decode_roundtrip::<#output_tokens>(&encoded)
+                .map_err(|_| ::proptest::test_runner::TestCaseError::fail("unable to decode method output"))?;
+            ::proptest::prop_assert_eq!(decoded, __obce_output);
+        }
+    })
+}
+
+/// Compute a method signature hash, that changes whenever method input or output types are changed.
+///
+/// Types are hashed in the `(Input1,Input2)->Output` form using their [canonical representation](canonical_type),
+/// thus spelling the same type differently (for example, `u32` and `::core::primitive::u32`)
+/// doesn't change the hash.
+///
+/// The hash is computed over type tokens rather than resolved types, see [`canonical_type`] for limitations.
+fn signature_hash(input_tys: &[&Type], output_ty: Option<&Type>) -> u32 {
+    let inputs = input_tys.iter().map(|ty| canonical_type(ty)).join(",");
+    let output = output_ty.map_or_else(|| "()".to_owned(), canonical_type);
+
+    into_u32(format!("({inputs})->{output}"))
+}
+
+/// Get a canonical representation of a type.
+///
+/// Primitive and prelude types from `core`, `alloc` and `std` are reduced to their names, so that
+/// `u32` and `::core::primitive::u32` are equal. Other type paths are kept as written (without the
+/// leading `::`), so that `a::Foo` and `b::Foo` are different. References are replaced with the
+/// referenced types (since they share the encoding), and whitespace is removed.
+///
+/// Type definitions can't be resolved during macro expansion, thus the same user-defined type
+/// spelled using different paths (or type aliases) hashes differently, and changes inside of
+/// user-defined types don't affect the canonical representation.
+fn canonical_type(ty: &Type) -> String {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => {
+            let is_std_path = path.segments.len() > 1
+                && path
+                    .segments
+                    .first()
+                    .is_some_and(|segment| STD_CRATES.iter().any(|krate| segment.ident == krate));
+            let is_prelude_type = path
+                .segments
+                .last()
+                .is_some_and(|segment| PRELUDE_TYPES.iter().any(|ty| segment.ident == ty));
+
+            if is_std_path && is_prelude_type {
+                path.segments.last().map(canonical_segment).unwrap_or_default()
+            } else {
+                path.segments.iter().map(canonical_segment).join("::")
+            }
+        }
+        Type::Reference(reference) => canonical_type(&reference.elem),
+        Type::Paren(paren) => canonical_type(&paren.elem),
+        Type::Group(group) => canonical_type(&group.elem),
+        Type::Tuple(tuple) => format!("({})", tuple.elems.iter().map(canonical_type).join(",")),
+        Type::Array(array) => {
+            let len = &array.len;
+            format!("[{};{}]", canonical_type(&array.elem), without_whitespace(quote!(#len)))
+        }
+        Type::Slice(slice) => format!("[{}]", canonical_type(&slice.elem)),
+        ty => without_whitespace(quote!(#ty)),
+    }
+}
+
+fn canonical_segment(segment: &PathSegment) -> String {
+    let ident = &segment.ident;
+
+    match &segment.arguments {
+        PathArguments::None => ident.to_string(),
+        PathArguments::AngleBracketed(arguments) => {
+            let arguments = arguments
+                .args
+                .iter()
+                .map(|argument| {
+                    match argument {
+                        GenericArgument::Type(ty) => canonical_type(ty),
+                        argument => without_whitespace(quote!(#argument)),
+                    }
+                })
+                .join(",");
+
+            format!("{ident}<{arguments}>")
+        }
+        arguments => format!("{ident}{}", without_whitespace(quote!(#arguments))),
+    }
+}
+
+fn without_whitespace(tokens: TokenStream) -> String {
+    tokens
+        .to_string()
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .collect()
+}
+
+/// Convert a method argument or output type into a type suitable for [`MethodDescription`] usage.
+///
+/// Associated types are resolved separately by each side of a chain extension,
+/// so they are replaced with `AssociatedType` placeholders.
+fn description_type(ty: &Type) -> Type {
+    let mut ty = ty.clone();
+
+    replace_assoc_types(&mut ty, |ident| {
+        let name_hash = into_u32(ident);
+
+        parse_quote! {
+            ::obce::codegen::AssociatedType<#name_hash>
+        }
+    });
+
+    ty
+}
+
+fn prepare_assoc_type(type_item: &mut TraitItemType) -> Result<(), Error> {
+    if !type_item.generics.params.is_empty() {
+        return Err(format_err_spanned!(
+            type_item.generics,
+            "generic associated types are not supported in trait definitions"
+        ))
+    }
+
+    if type_item.colon_token.is_none() {
+        type_item.colon_token = Some(Default::default());
+    }
+
+    type_item.bounds.push(parse_quote! { ::scale::Codec });
+
+    type_item
+        .generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote! { Self: Sized });
+
+    Ok(())
+}
+
+fn verify_helper_method(method_item: &TraitItemMethod) -> Result<(), Error> {
+    let (obce_attrs, _) = method_item.attrs.iter().split_attrs()?;
+
+    if let Some(attr) = obce_attrs.first() {
+        return Err(format_err_spanned!(
+            attr,
+            "methods with default implementation are not chain extension methods and can't have OBCE attributes"
+        ))
+    }
+
+    Ok(())
+}
+
 fn find_id<'a, I: IntoIterator<Item = &'a NestedMeta>>(iter: I) -> Result<Option<u16>, Error> {
     iter.into_iter()
         .find_map(|arg| {
             match arg {
-                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("id") => {
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("id") => Some(parse_id(value)),
+                _ => None,
+            }
+        })
+        .transpose()
+}
+
+fn find_alias_ids<'a, I: IntoIterator<Item = &'a NestedMeta>>(iter: I) -> Result<Vec<u16>, Error> {
+    iter.into_iter()
+        .filter_map(|arg| {
+            match arg {
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("alias_id") => Some(parse_id(value)),
+                _ => None,
+            }
+        })
+        .try_collect()
+}
+
+fn parse_id(value: &MetaNameValue) -> Result<u16, Error> {
+    match &value.lit {
+        Lit::Int(lit_int) => lit_int.base10_parse::<u16>(),
+        Lit::Str(lit_str) => Ok(into_u16(lit_str.value())),
+        _ => {
+            Err(format_err_spanned!(
+                value,
+                "{} should be integer or string",
+                value.path.get_ident().unwrap()
+            ))
+        }
+    }
+}
+
+fn find_version<'a, I: IntoIterator<Item = &'a NestedMeta>>(iter: I, name: &str) -> Result<Option<u16>, Error> {
+    iter.into_iter()
+        .find_map(|arg| {
+            match arg {
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident(name) => {
                     Some(match &value.lit {
                         Lit::Int(lit_int) => lit_int.base10_parse::<u16>(),
-                        Lit::Str(lit_str) => Ok(into_u16(lit_str.value())),
-                        _ => Err(format_err_spanned!(value, "id should be integer or string")),
+                        _ => Err(format_err_spanned!(value, "{name} should be integer")),
                     })
                 }
                 _ => None,
@@ -9,7 +9,13 @@ use syn::{
     parse2,
     Error,
     Expr,
+    ExprLit,
+    Field,
+    Fields,
     ItemEnum,
+    Lit,
+    Type,
+    TypePath,
 };
 
 use crate::{
@@ -32,6 +38,7 @@ struct ErrorAttrs {
 struct ErrorVariantAttrs {
     critical: Option<()>,
     ret_val: Option<Expr>,
+    unknown_status: Option<()>,
 }
 
 struct RetValInfo<'a> {
@@ -50,6 +57,8 @@ pub fn generate(attrs: TokenStream, input: TokenStream) -> Result<TokenStream, E
 
     let mut ret_val_variants = vec![];
 
+    let mut unknown_status_variant = None;
+
     for variant in enum_item.variants.iter_mut() {
         let variant_name = &variant.ident;
 
@@ -82,8 +91,36 @@ pub fn generate(attrs: TokenStream, input: TokenStream) -> Result<TokenStream, E
             }
         }
 
+        if let (Some(ret_val), Some(_)) = (&variant_attrs.ret_val, variant_attrs.unknown_status) {
+            return Err(format_err_spanned!(
+                ret_val,
+                "variant can't be marked as both `#[obce(ret_val = \"...\")]` and `#[obce(unknown_status)]`",
+            ))
+        }
+
         if let Some(ret_val) = variant_attrs.ret_val {
+            if is_reserved_ret_val(&ret_val) {
+                return Err(format_err_spanned!(
+                    ret_val,
+                    "`u32::MAX` status code is reserved for methods that are unknown to the chain extension"
+                ))
+            }
+
             ret_val_variants.push(RetValInfo { variant_name, ret_val });
+        } else if variant_attrs.unknown_status.is_some() {
+            if !holds_status_code(&variant.fields) {
+                return Err(format_err_spanned!(
+                    variant,
+                    "variant marked as `#[obce(unknown_status)]` should have a single unnamed `u32` field",
+                ))
+            }
+
+            if unknown_status_variant.replace(variant_name.clone()).is_some() {
+                return Err(format_err_spanned!(
+                    variant,
+                    "only one enum variant can be marked as `#[obce(unknown_status)]`",
+                ))
+            }
         } else if enum_attrs.require_ret_val && !ret_val_variants.is_empty() {
             return Err(format_err_spanned!(
                 variant,
@@ -98,6 +135,20 @@ pub fn generate(attrs: TokenStream, input: TokenStream) -> Result<TokenStream, E
         return Err(format_err_spanned!(expr, "ret_val value is used twice"))
     }
 
+    // Expressions can't be evaluated during macro expansion, so the reserved status code
+    // is additionally checked at compile time.
+    let reserved_ret_val_checks: Vec<_> = ret_val_variants
+        .iter()
+        .map(|RetValInfo { ret_val, .. }| {
+            quote! {
+                const _: () = ::core::assert!(
+                    (#ret_val) != ::obce::codegen::UNKNOWN_FUNCTION_STATUS_CODE,
+                    "`u32::MAX` status code is reserved for methods that are unknown to the chain extension"
+                );
+            }
+        })
+        .collect();
+
     let formatted_ret_val = ret_val_variants.iter().map(|RetValInfo { variant_name, ret_val }| {
         quote! {
             #ident::#variant_name => Ok(Self::Converging(#ret_val)),
@@ -120,6 +171,45 @@ pub fn generate(attrs: TokenStream, input: TokenStream) -> Result<TokenStream, E
         }
     };
 
+    let formatted_from_ret_val = ret_val_variants.iter().map(|RetValInfo { variant_name, ret_val }| {
+        quote! {
+            status_code if status_code == (#ret_val) => Some(Self::#variant_name),
+        }
+    });
+
+    // Status codes that don't correspond to any variant are either returned as a user-specified variant,
+    // or left to the caller, which returns them as `CallError::Status`.
+    let unknown_from_ret_val = match &unknown_status_variant {
+        Some(variant_name) => quote! { Some(Self::#variant_name(status_code)) },
+        None => quote! { None },
+    };
+
+    let status_code_impl = quote! {
+        impl #impl_generics ::obce::ink_lang::FromRetVal for #ident #ty_generics #where_clause {
+            fn from_ret_val(status_code: ::core::primitive::u32) -> Option<Self> {
+                match status_code {
+                    #(#formatted_from_ret_val)*
+                    status_code => #unknown_from_ret_val,
+                }
+            }
+        }
+
+        impl #impl_generics ::obce::ink_lang::env::chain_extension::FromStatusCode
+            for #ident #ty_generics
+            #where_clause
+        {
+            fn from_status_code(status_code: ::core::primitive::u32) -> Result<(), Self> {
+                match status_code {
+                    0 => Ok(()),
+                    _ => match <Self as ::obce::ink_lang::FromRetVal>::from_ret_val(status_code) {
+                        Some(error) => Err(error),
+                        None => panic!("unknown chain extension status code: {}", status_code),
+                    },
+                }
+            }
+        }
+    };
+
     Ok(quote! {
         #[derive(Debug, Copy, Clone, PartialEq, Eq, ::scale::Encode, ::scale::Decode)]
         #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
@@ -127,7 +217,34 @@ pub fn generate(attrs: TokenStream, input: TokenStream) -> Result<TokenStream, E
 
         #critical_variant
 
+        #(#reserved_ret_val_checks)*
+
         #[cfg(feature = "substrate")]
         #ret_val_impl
+
+        #[cfg(feature = "ink")]
+        #status_code_impl
     })
 }
+
+/// Check whether variant fields consist of a single unnamed `u32` status code.
+fn holds_status_code(fields: &Fields) -> bool {
+    let Fields::Unnamed(fields) = fields else { return false };
+
+    match fields.unnamed.iter().exactly_one() {
+        Ok(Field {
+            ty: Type::Path(TypePath { qself: None, path }),
+            ..
+        }) => path.segments.last().is_some_and(|segment| segment.ident == "u32"),
+        _ => false,
+    }
+}
+
+/// Check whether `ret_val` is an integer literal that is equal to the reserved
+/// unknown function status code (`u32::MAX`).
+fn is_reserved_ret_val(ret_val: &Expr) -> bool {
+    match ret_val {
+        Expr::Lit(ExprLit { lit: Lit::Int(lit), .. }) => lit.base10_parse::<u64>().ok() == Some(u32::MAX as u64),
+        _ => false,
+    }
+}
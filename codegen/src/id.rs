@@ -42,3 +42,25 @@ pub fn generate(input: TokenStream) -> Result<TokenStream, Error> {
         }
     }
 }
+
+pub fn generate_method_hash(input: TokenStream) -> Result<TokenStream, Error> {
+    let path: Path = syn::parse2(input)?;
+
+    // Method hash is dependent solely on the method name, however we still require
+    // the full path to keep the macro usage consistent with `id!`.
+    match path.segments.iter().collect_tuple() {
+        Some((_, method)) => {
+            let method_hash = into_u32(&method.ident);
+
+            Ok(quote! {
+                #method_hash
+            })
+        }
+        None => {
+            Err(format_err_spanned!(
+                path,
+                "method_hash macro supports only two-segment paths (ChainExtension::method)"
+            ))
+        }
+    }
+}
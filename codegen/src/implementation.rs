@@ -21,8 +21,11 @@
 
 use crate::{
     format_err_spanned,
+    types::AttributeArgs,
     utils::{
         into_u32,
+        mentions_bindings,
+        mentions_self,
         AttributeParser,
         InputBindings,
         LitOrPath,
@@ -45,24 +48,32 @@ use syn::{
     parse_quote,
     parse_str,
     punctuated::Punctuated,
+    Block,
     Error,
     Expr,
+    ExprLit,
+    ExprRange,
+    FnArg,
     GenericArgument,
     Generics,
     ImplItem,
+    ImplItemMethod,
     ItemImpl,
     Lit,
     Meta,
+    MetaNameValue,
     NestedMeta,
     Path,
     PathArguments,
+    Stmt,
     Token,
     Type,
 };
 use tuple::Map;
 
-pub fn generate(_attrs: TokenStream, input: TokenStream) -> Result<TokenStream, Error> {
+pub fn generate(attrs: TokenStream, input: TokenStream) -> Result<TokenStream, Error> {
     let impl_item: ItemImpl = parse2(input).unwrap();
+    let impl_attrs = ImplAttrs::new(parse2::<AttributeArgs>(attrs)?.iter())?;
 
     let mut original_implementation = impl_item.clone();
 
@@ -80,7 +91,7 @@ pub fn generate(_attrs: TokenStream, input: TokenStream) -> Result<TokenStream,
         method_item.attrs = other_attrs;
     }
 
-    let chain_extension = chain_extension_trait_impl(impl_item)?;
+    let chain_extension = chain_extension_trait_impl(impl_item, &impl_attrs)?;
 
     Ok(quote! {
         // Implementation of the trait for `ExtensionContext` with main logic.
@@ -91,8 +102,371 @@ pub fn generate(_attrs: TokenStream, input: TokenStream) -> Result<TokenStream,
     })
 }
 
+struct ImplAttrs {
+    require_weight: bool,
+    trap_unknown_functions: bool,
+    base_weight: Option<Expr>,
+    weight_info: Option<Type>,
+    benchmarks: Option<BenchmarkAttrs>,
+}
+
+#[derive(Default)]
+struct BenchmarkAttrs {
+    ext: Option<Expr>,
+}
+
+impl ImplAttrs {
+    fn new<'a, I: IntoIterator<Item = &'a NestedMeta> + Clone>(iter: I) -> Result<Self, Error> {
+        let require_weight = match iter.clone().find_by_name("require_weight") {
+            Some((LitOrPath::Path, _)) => true,
+            Some((_, ident)) => {
+                return Err(format_err_spanned!(
+                    ident,
+                    "`require_weight` attribute accepts no value"
+                ))
+            }
+            None => false,
+        };
+
+        // Unknown methods return a status code by default, `unknown_function_status` is still accepted
+        // for implementations that enabled it explicitly.
+        let unknown_function_status = match iter.clone().find_by_name("unknown_function_status") {
+            Some((LitOrPath::Path, ident)) => Some(ident),
+            Some((_, ident)) => {
+                return Err(format_err_spanned!(
+                    ident,
+                    "`unknown_function_status` attribute accepts no value"
+                ))
+            }
+            None => None,
+        };
+
+        let trap_unknown_functions = match iter.clone().find_by_name("trap_unknown_functions") {
+            Some((LitOrPath::Path, _)) => true,
+            Some((_, ident)) => {
+                return Err(format_err_spanned!(
+                    ident,
+                    "`trap_unknown_functions` attribute accepts no value"
+                ))
+            }
+            None => false,
+        };
+
+        if let (Some(ident), true) = (unknown_function_status, trap_unknown_functions) {
+            return Err(format_err_spanned!(
+                ident,
+                "`unknown_function_status` can't be used together with `trap_unknown_functions`"
+            ))
+        }
+
+        let base_weight = match iter.clone().find_by_name("base_weight") {
+            Some((LitOrPath::Lit(Lit::Str(expr)), _)) => Some(parse_str::<Expr>(&expr.value())?),
+            Some((_, ident)) => {
+                return Err(format_err_spanned!(
+                    ident,
+                    "`base_weight` attribute should contain an expression that returns `Weight`"
+                ))
+            }
+            None => None,
+        };
+
+        let weight_info = match iter.clone().find_by_name("weight_info") {
+            Some((LitOrPath::Lit(Lit::Str(ty)), _)) => Some(parse_str::<Type>(&ty.value())?),
+            Some((_, ident)) => {
+                return Err(format_err_spanned!(
+                    ident,
+                    "`weight_info` attribute should contain a type that implements the generated `WeightInfo` trait"
+                ))
+            }
+            None => None,
+        };
+
+        let benchmarks = iter
+            .into_iter()
+            .find_map(|attr| {
+                match attr {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("benchmarks") => {
+                        Some(Ok(BenchmarkAttrs::default()))
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("benchmarks") => {
+                        Some(BenchmarkAttrs::new(&list.nested))
+                    }
+                    _ => None,
+                }
+            })
+            .transpose()?;
+
+        Ok(Self {
+            require_weight,
+            trap_unknown_functions,
+            base_weight,
+            weight_info,
+            benchmarks,
+        })
+    }
+}
+
+impl BenchmarkAttrs {
+    fn new(params: &Punctuated<NestedMeta, Token![,]>) -> Result<Self, Error> {
+        let ext = match params.iter().find_by_name("ext") {
+            Some((LitOrPath::Lit(Lit::Str(expr)), _)) => Some(parse_str::<Expr>(&expr.value())?),
+            Some((_, ident)) => {
+                return Err(format_err_spanned!(
+                    ident,
+                    "`ext` attribute should contain an expression that creates `Ext` implementation"
+                ))
+            }
+            None => None,
+        };
+
+        Ok(Self { ext })
+    }
+}
+
+/// Per-method benchmark parameters, provided via `#[obce(benchmark(...))]`.
+#[derive(Default)]
+struct MethodBenchmark {
+    components: Vec<(Ident, Expr, Expr)>,
+    setup: Vec<Stmt>,
+    args: Option<Punctuated<Expr, Token![,]>>,
+}
+
+impl MethodBenchmark {
+    fn new<'a, I: IntoIterator<Item = &'a NestedMeta>>(iter: I) -> Result<Self, Error> {
+        let params = iter.into_iter().find_map(|attr| {
+            let NestedMeta::Meta(Meta::List(list)) = attr else {
+                return None
+            };
+
+            list.path.is_ident("benchmark").then_some(&list.nested)
+        });
+
+        let Some(params) = params else {
+            return Ok(Self::default())
+        };
+
+        let components = params
+            .iter()
+            .find_map(|attr| {
+                let NestedMeta::Meta(Meta::List(list)) = attr else {
+                    return None
+                };
+
+                list.path.is_ident("components").then_some(&list.nested)
+            })
+            .into_iter()
+            .flatten()
+            .map(|component| {
+                let NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                    path,
+                    lit: Lit::Str(range),
+                    ..
+                })) = component
+                else {
+                    return Err(format_err_spanned!(
+                        component,
+                        r#"components should be specified as `name = "from .. to"`"#
+                    ))
+                };
+
+                let Some(name) = path.get_ident() else {
+                    return Err(format_err_spanned!(path, "component name should be an identifier"))
+                };
+
+                match parse_str::<ExprRange>(&range.value())? {
+                    ExprRange {
+                        from: Some(from),
+                        to: Some(to),
+                        ..
+                    } => Ok((name.clone(), *from, *to)),
+                    _ => Err(format_err_spanned!(range, "component range should have both bounds")),
+                }
+            })
+            .try_collect()?;
+
+        let setup = match params.iter().find_by_name("setup") {
+            Some((LitOrPath::Lit(Lit::Str(setup)), _)) => {
+                parse_str::<Block>(&format!("{{ {} }}", setup.value()))?.stmts
+            }
+            Some((_, ident)) => {
+                return Err(format_err_spanned!(
+                    ident,
+                    "`setup` attribute should contain a list of statements"
+                ))
+            }
+            None => Vec::new(),
+        };
+
+        let args = match params.iter().find_by_name("args") {
+            Some((LitOrPath::Lit(Lit::Str(args)), _)) => {
+                let parser = Punctuated::<Expr, Token![,]>::parse_terminated;
+                Some(parser.parse_str(&args.value())?)
+            }
+            Some((_, ident)) => {
+                return Err(format_err_spanned!(
+                    ident,
+                    "`args` attribute should contain a comma-separated expression list"
+                ))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            components,
+            setup,
+            args,
+        })
+    }
+}
+
+/// Generate `WeightInfo` trait and `frame_benchmarking` benchmarks for chain extension methods.
+#[allow(non_snake_case)]
+fn handle_benchmarks(
+    impl_item: &ItemImpl,
+    impl_attrs: &ImplAttrs,
+    trait_ident: &Ident,
+    weight_info_trait: &Ident,
+    dyn_trait: &TokenStream,
+    extension: &GenericArgument,
+    main_generics: &Generics,
+) -> Result<TokenStream, Error> {
+    if impl_attrs.benchmarks.is_none() && impl_attrs.weight_info.is_none() {
+        return Ok(quote! {})
+    }
+
+    let methods: Vec<_> = impl_item
+        .items
+        .iter()
+        .filter_map(|item| {
+            if let ImplItem::Method(method) = item {
+                Some(method)
+            } else {
+                None
+            }
+        })
+        .map(|method| {
+            let (obce_attrs, _) = method.attrs.iter().split_attrs()?;
+            Result::<_, Error>::Ok((method, MethodBenchmark::new(obce_attrs.iter())?))
+        })
+        .try_collect()?;
+
+    let weight_info_methods = methods.iter().map(|(method, benchmark)| {
+        let method_name = &method.sig.ident;
+        let components = benchmark.components.iter().map(|(name, _, _)| name);
+
+        quote! {
+            fn #method_name(#(#components: u32),*) -> ::obce::substrate::frame_support::dispatch::Weight;
+        }
+    });
+
+    let weight_info = quote! {
+        /// Weight functions needed for chain extension methods.
+        pub trait #weight_info_trait {
+            #(#weight_info_methods)*
+        }
+    };
+
+    let Some(BenchmarkAttrs { ext }) = &impl_attrs.benchmarks else {
+        return Ok(weight_info)
+    };
+
+    let ext = ext
+        .as_ref()
+        .map(ToTokens::to_token_stream)
+        .unwrap_or_else(|| quote! { () });
+
+    let benchmarks: Vec<_> = methods
+        .iter()
+        .map(|(method, benchmark)| {
+            let method_name = &method.sig.ident;
+            let hash = into_u32(method_name);
+
+            let args = match &benchmark.args {
+                Some(args) => args.iter().collect(),
+                None if InputBindings::from_iter(&method.sig.inputs).iter_call_params().len() == 0 => Vec::new(),
+                None => {
+                    return Err(format_err_spanned!(
+                        method_name,
+                        r#"methods with arguments require `#[obce(benchmark(args = "..."))]` to be benchmarked"#
+                    ))
+                }
+            };
+
+            let components = benchmark
+                .components
+                .iter()
+                .map(|(name, from, to)| quote! { let #name in (#from) .. (#to); });
+            let setup = &benchmark.setup;
+
+            Ok(quote! {
+                #method_name {
+                    #(#components)*
+                    #(#setup)*
+                    let __input = <_ as ::scale::Encode>::encode(&(#(#args,)*));
+                    let mut __extension = <#extension as ::core::default::Default>::default();
+                    let __env = ::obce::substrate::benchmarking::BenchmarkEnvironment::<_, T>::new(
+                        <#dyn_trait as ::obce::codegen::ExtensionDescription>::ID,
+                        <#dyn_trait as ::obce::codegen::MethodDescription<#hash>>::ID,
+                        __input,
+                        #ext,
+                    );
+                }: {
+                    let __result = <#extension as ::obce::substrate::CallableChainExtension<_, T, _>>::call(
+                        &mut __extension,
+                        __env,
+                    );
+                    assert!(__result.is_ok(), "chain extension call failed");
+                }
+            })
+        })
+        .try_collect()?;
+
+    let where_clause = main_generics
+        .where_clause
+        .as_ref()
+        .filter(|where_clause| !where_clause.predicates.is_empty())
+        .map(|where_clause| {
+            let predicates = where_clause.predicates.iter();
+
+            quote! {
+                where_clause { where #(#predicates,)* }
+            }
+        });
+
+    let benchmarks_struct = format_ident!("{}Benchmarks", trait_ident);
+    let benchmarks_mod = format_ident!("__obce_benchmarks_{}", trait_ident);
+    let benchmarks_doc = format!(
+        "Benchmarks of [`{trait_ident}`] chain extension methods, that can be added to the runtime benchmarks."
+    );
+
+    // Benchmarks are implemented on a local type instead of the pallet, which allows benchmarking
+    // chain extensions that are attached to foreign pallets or pallets that have their own benchmarks.
+    Ok(quote! {
+        #weight_info
+
+        #[cfg(feature = "runtime-benchmarks")]
+        #[doc = #benchmarks_doc]
+        pub struct #benchmarks_struct<T>(::core::marker::PhantomData<T>);
+
+        #[cfg(feature = "runtime-benchmarks")]
+        #[allow(non_snake_case)]
+        mod #benchmarks_mod {
+            use super::*;
+            use super::#benchmarks_struct as Pallet;
+            #[allow(unused_imports)]
+            use ::obce::substrate::frame_system::{self, Config};
+
+            ::obce::substrate::frame_benchmarking::benchmarks! {
+                #where_clause
+
+                #(#benchmarks)*
+            }
+        }
+    })
+}
+
 #[allow(non_snake_case)]
-fn chain_extension_trait_impl(mut impl_item: ItemImpl) -> Result<TokenStream, Error> {
+fn chain_extension_trait_impl(mut impl_item: ItemImpl, impl_attrs: &ImplAttrs) -> Result<TokenStream, Error> {
     let context = ExtensionContext::try_from(&impl_item)?;
 
     let namespace = quote! { ::obce::substrate::pallet_contracts::chain_extension:: };
@@ -132,13 +506,30 @@ fn chain_extension_trait_impl(mut impl_item: ItemImpl) -> Result<TokenStream, Er
 
     let trait_;
     let dyn_trait;
-    if let Some((_, path, _)) = impl_item.trait_ {
+    if let Some((_, path, _)) = &impl_item.trait_ {
         trait_ = path.clone();
         dyn_trait = quote! { dyn #path };
     } else {
         return Err(format_err_spanned!(impl_item, "expected impl trait block",))
     }
 
+    let trait_ident = &trait_.segments.last().unwrap().ident;
+    let weight_info_trait = format_ident!("{}WeightInfo", trait_ident);
+    let weight_info = impl_attrs
+        .weight_info
+        .as_ref()
+        .map(|weight_info| (weight_info, &weight_info_trait));
+
+    let benchmarks = handle_benchmarks(
+        &impl_item,
+        impl_attrs,
+        trait_ident,
+        &weight_info_trait,
+        &dyn_trait,
+        &extension,
+        &main_generics,
+    )?;
+
     let methods: Vec<_> = impl_item
         .items
         .iter_mut()
@@ -154,6 +545,13 @@ fn chain_extension_trait_impl(mut impl_item: ItemImpl) -> Result<TokenStream, Er
 
             method.attrs = other_attrs;
 
+            if impl_attrs.require_weight && find_weight_params(obce_attrs.iter()).is_none() {
+                return Err(format_err_spanned!(
+                    method.sig.ident,
+                    "method has no weight annotation, consider adding `#[obce(weight(...))]`"
+                ))
+            }
+
             let hash = into_u32(&method.sig.ident);
             let method_name = &method.sig.ident;
 
@@ -161,33 +559,77 @@ fn chain_extension_trait_impl(mut impl_item: ItemImpl) -> Result<TokenStream, Er
             let lhs_pat = input_bindings.lhs_pat(None);
             let call_params = input_bindings.iter_call_params();
 
-            let (weight_tokens, pre_charge) = handle_weight_attribute(&input_bindings, obce_attrs.iter())?;
+            // Weights that don't depend on the method input are charged before reading it,
+            // so that contracts can't make the chain extension decode the input for free.
+            let weight_depends_on_input = weight_depends_on_input(&input_bindings, obce_attrs.iter());
+            let no_bindings = InputBindings::from_iter(std::iter::empty());
+            let weight_bindings = if weight_depends_on_input {
+                &input_bindings
+            } else {
+                &no_bindings
+            };
+
+            let (weight_tokens, pre_charge) =
+                handle_weight_attribute(weight_bindings, &T, method_name, weight_info, obce_attrs.iter())?;
             let ret_val_tokens = handle_ret_val_attribute(obce_attrs.iter());
+            let ret_val_check = ret_val_check(&trait_, &dyn_trait, hash, ret_val_tokens.is_some());
+            let (input_len_check, read_input) = handle_input_attributes(method, obce_attrs.iter())?;
+            let (input_byte_charge, output_byte_weight) = handle_per_byte_weight(obce_attrs.iter())?;
+            let actual_weight = handle_actual_weight(obce_attrs.iter())?;
+
+            let charge_weight = if pre_charge || actual_weight.is_some() {
+                quote! {
+                    let charged_weight = #weight_tokens;
+                }
+            } else {
+                quote! {
+                    #weight_tokens;
+                }
+            };
 
-            let (read_with_charge, pre_charge_arg) = if pre_charge {
-                (
-                    quote! {
-                        let pre_charged = #weight_tokens;
-                        let #lhs_pat = env.read_as_unbounded(len)?;
-                    },
-                    quote! {
-                        Some(pre_charged)
-                    },
-                )
+            let read_with_charge = if pre_charge || !weight_depends_on_input {
+                quote! {
+                    #input_len_check
+                    #input_byte_charge
+                    #charge_weight
+                    let #lhs_pat = #read_input;
+                }
             } else {
-                (
-                    quote! {
-                        let #lhs_pat = env.read_as_unbounded(len)?;
-                        #weight_tokens;
-                    },
-                    quote! {
-                        None
-                    },
-                )
+                quote! {
+                    #input_len_check
+                    #input_byte_charge
+                    let #lhs_pat = #read_input;
+                    #charge_weight
+                }
             };
 
+            // When the actual weight is provided, the charged amount is kept by the dispatcher
+            // to adjust it after the call.
+            let pre_charge_arg = if pre_charge && actual_weight.is_none() {
+                quote! {
+                    Some(charged_weight)
+                }
+            } else {
+                quote! {
+                    None
+                }
+            };
+
+            let adjust_weight = actual_weight.map(|actual_weight| {
+                quote! {
+                    let actual_weight = {
+                        #[allow(unused_variables)]
+                        let result = &result;
+                        #actual_weight
+                    };
+                    context.env.adjust_weight(charged_weight, actual_weight);
+                }
+            });
+
             Result::<_, Error>::Ok(quote! {
-                <#dyn_trait as ::obce::codegen::MethodDescription<#hash>>::ID => {
+                func_id if func_id == <#dyn_trait as ::obce::codegen::MethodDescription<#hash>>::ID
+                    || <#dyn_trait as ::obce::codegen::MethodDescription<#hash>>::ALIAS_IDS.contains(&func_id) => {
+                    #ret_val_check
                     #read_with_charge
                     let mut context = ::obce::substrate::ExtensionContext::new(self, env, #pre_charge_arg);
                     #[allow(clippy::unnecessary_mut_passed)]
@@ -199,13 +641,33 @@ fn chain_extension_trait_impl(mut impl_item: ItemImpl) -> Result<TokenStream, Er
                     // If result is `Result` and `Err` is critical, return from the `call`.
                     // Otherwise, try to convert result to RetVal, and return it or encode the result into the buffer.
                     let result = ::obce::to_critical_error!(result)?;
+                    #adjust_weight
                     #ret_val_tokens
-                    <_ as ::scale::Encode>::using_encoded(&result, |w| context.env.write(w, true, None))?;
+                    <_ as ::scale::Encode>::using_encoded(&result, |w| context.env.write(w, true, #output_byte_weight))?;
                 },
             })
         })
         .try_collect()?;
 
+    // Unknown methods return a status code by default, which `try_*` methods report as `CallError::UnknownFunction`.
+    // Chain extensions, that are called by contracts with `ignore_error_code` glue, may trap instead,
+    // since such contracts would otherwise decode an output that was never written.
+    let unknown_function = if impl_attrs.trap_unknown_functions {
+        quote! {
+            ::core::result::Result::Err(::obce::substrate::CriticalError::Other("InvalidFunctionId"))?
+        }
+    } else {
+        quote! {
+            return Ok(#namespace RetVal::Converging(::obce::codegen::UNKNOWN_FUNCTION_STATUS_CODE))
+        }
+    };
+
+    let base_weight = impl_attrs.base_weight.as_ref().map(|base_weight| {
+        quote! {
+            env.charge_weight(#base_weight)?;
+        }
+    });
+
     Ok(quote! {
         impl #callable_impls ::obce::substrate::CallableChainExtension<#E, #T, #Env> for #extension
             #callable_where
@@ -215,12 +677,24 @@ fn chain_extension_trait_impl(mut impl_item: ItemImpl) -> Result<TokenStream, Er
                 ::obce::substrate::CriticalError
             > {
                 let len = env.in_len();
+                #base_weight
 
                 match env.func_id() {
                     #(#methods)*
-                    _ => ::core::result::Result::Err(::obce::substrate::CriticalError::Other(
-                        "InvalidFunctionId"
-                    ))?,
+                    // Capability handshake, that allows contracts to detect supported features.
+                    ::obce::codegen::VERSION_FUNCTION_ID => {
+                        let version = <#dyn_trait as ::obce::codegen::ExtensionDescription>::VERSION;
+                        <_ as ::scale::Encode>::using_encoded(&version, |w| env.write(w, true, None))?;
+                    },
+                    ::obce::codegen::SUPPORTED_METHODS_FUNCTION_ID => {
+                        let method_ids = <#dyn_trait as ::obce::codegen::ExtensionDescription>::METHOD_IDS;
+                        <_ as ::scale::Encode>::using_encoded(&method_ids, |w| env.write(w, true, None))?;
+                    },
+                    ::obce::codegen::ABI_HASH_FUNCTION_ID => {
+                        let abi_hash = <#dyn_trait as ::obce::codegen::ExtensionDescription>::ABI_HASH;
+                        <_ as ::scale::Encode>::using_encoded(&abi_hash, |w| env.write(w, true, None))?;
+                    },
+                    _ => #unknown_function,
                 };
 
                 Ok(#namespace RetVal::Converging(0))
@@ -241,6 +715,8 @@ fn chain_extension_trait_impl(mut impl_item: ItemImpl) -> Result<TokenStream, Er
         impl #main_impls #namespace RegisteredChainExtension<#T> for #extension #main_where {
             const ID: ::core::primitive::u16 = <#dyn_trait as ::obce::codegen::ExtensionDescription>::ID;
         }
+
+        #benchmarks
     })
 }
 
@@ -368,8 +844,99 @@ fn handle_ret_val_attribute<'a, I: IntoIterator<Item = &'a NestedMeta>>(iter: I)
     })
 }
 
+/// Check that the method is marked with `#[obce(ret_val)]` on both sides of a chain extension, or on neither.
+fn ret_val_check(trait_: &Path, dyn_trait: &TokenStream, hash: u32, ret_val: bool) -> TokenStream {
+    let message = if ret_val {
+        "method is marked with `#[obce(ret_val)]` in the implementation, but not in the definition"
+    } else {
+        "method is marked with `#[obce(ret_val)]` in the definition, but not in the implementation"
+    };
+
+    let assertion = quote! {
+        ::core::assert!(
+            <#dyn_trait as ::obce::codegen::MethodDescription<#hash>>::RET_VAL == #ret_val,
+            #message
+        )
+    };
+
+    // Constant items can't use generic parameters of the implementation,
+    // thus generic traits are checked only when the chain extension is instantiated.
+    if trait_.segments.iter().all(|segment| segment.arguments.is_empty()) {
+        quote! {
+            const _: () = #assertion;
+        }
+    } else {
+        quote! {
+            const { #assertion };
+        }
+    }
+}
+
+fn handle_input_attributes<'a, I: IntoIterator<Item = &'a NestedMeta> + Clone>(
+    method: &ImplItemMethod,
+    iter: I,
+) -> Result<(Option<TokenStream>, TokenStream), Error> {
+    let find_limit = |name| {
+        match iter.clone().find_by_name(name) {
+            Some((LitOrPath::Lit(Lit::Int(limit)), _)) => limit.base10_parse::<u32>().map(Some),
+            Some((_, ident)) => Err(format_err_spanned!(ident, "{name} should be integer")),
+            None => Ok(None),
+        }
+    };
+
+    let input_len_check = find_limit("max_input_len")?.map(|max_input_len| {
+        quote! {
+            if len > #max_input_len {
+                return Err(::obce::substrate::INPUT_LENGTH_EXCEEDED)
+            }
+        }
+    });
+
+    let input_tys: Vec<_> = method
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|input| {
+            if let FnArg::Typed(pat) = input {
+                Some(&*pat.ty)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let read_input = if let Some(max_depth) = find_limit("max_depth")? {
+        quote! {
+            ::obce::substrate::decode_with_depth_limit(&env.read(len)?, #max_depth)?
+        }
+    } else if input_tys.iter().any(|ty| mentions_self(ty)) {
+        // Input types that depend on `Self` can't be named outside of the implementation,
+        // so we can't choose the decoding strategy based on them.
+        quote! {
+            env.read_as_unbounded(len)?
+        }
+    } else {
+        // Inputs that implement `MaxEncodedLen` are read using `read_as`,
+        // which limits both input length and decoding depth.
+        quote! {{
+            #[allow(unused_imports)]
+            use ::obce::substrate::{
+                ReadBoundedInput as _,
+                ReadUnboundedInput as _,
+            };
+
+            (&::obce::substrate::InputReader::<(#(#input_tys),*)>::new()).read_input(&mut env, len)?
+        }}
+    };
+
+    Ok((input_len_check, read_input))
+}
+
 fn handle_weight_attribute<'a, I: IntoIterator<Item = &'a NestedMeta>>(
     input_bindings: &InputBindings,
+    substrate: &GenericArgument,
+    method_name: &Ident,
+    weight_info: Option<(&Type, &Ident)>,
     iter: I,
 ) -> Result<(Option<TokenStream>, bool), Error> {
     let weight_params = iter.into_iter().find_map(|attr| {
@@ -400,6 +967,7 @@ fn handle_weight_attribute<'a, I: IntoIterator<Item = &'a NestedMeta>>(
                     Some(handle_dispatch_weight(
                         ident,
                         input_bindings,
+                        substrate,
                         &dispatch_path.value(),
                         args.as_deref(),
                     )?),
@@ -415,7 +983,60 @@ fn handle_weight_attribute<'a, I: IntoIterator<Item = &'a NestedMeta>>(
             None => {}
         };
 
-        match weight_params.iter().find_by_name("expr") {
+        match (weight_params.iter().find_by_name("info"), weight_info) {
+            (Some((LitOrPath::Path, _)), Some(weight_info)) => {
+                return Ok((
+                    Some(handle_info_weight(input_bindings, method_name, weight_info, None)?),
+                    false,
+                ))
+            }
+            (Some((LitOrPath::Lit(Lit::Str(components)), _)), Some(weight_info)) => {
+                return Ok((
+                    Some(handle_info_weight(
+                        input_bindings,
+                        method_name,
+                        weight_info,
+                        Some(&components.value()),
+                    )?),
+                    false,
+                ))
+            }
+            (Some((_, ident)), None) => {
+                return Err(format_err_spanned!(
+                    ident,
+                    r#"`info` requires `#[obce::implementation(weight_info = "...")]` to be provided"#
+                ))
+            }
+            (Some((_, ident)), _) => {
+                return Err(format_err_spanned!(
+                    ident,
+                    "`info` attribute should contain a comma-separated list of component values"
+                ))
+            }
+            (None, _) => {}
+        }
+
+        match weight_params.iter().find_by_name("call") {
+            Some((LitOrPath::Lit(Lit::Str(call)), _)) => {
+                return Ok((
+                    Some(handle_call_weight(input_bindings, substrate, &call.value())?),
+                    false,
+                ))
+            }
+            Some((_, ident)) => {
+                return Err(format_err_spanned!(
+                    ident,
+                    "`call` attribute should contain an expression that builds a runtime call"
+                ))
+            }
+            None => {}
+        }
+
+        match weight_params
+            .iter()
+            .find_by_name("expr")
+            .or_else(|| weight_params.iter().find_by_name("max"))
+        {
             Some((LitOrPath::Lit(Lit::Str(expr)), _)) => {
                 let pre_charge = matches!(
                     weight_params.iter().find_by_name("pre_charge"),
@@ -430,21 +1051,224 @@ fn handle_weight_attribute<'a, I: IntoIterator<Item = &'a NestedMeta>>(
             Some((_, ident)) => {
                 return Err(format_err_spanned!(
                     ident,
-                    "`expr` attribute should contain an expression that returns `Weight`"
+                    "`{}` attribute should contain an expression that returns `Weight`",
+                    ident
                 ))
             }
             None => {}
         }
 
+        if let Some(db_weight) = handle_db_weight(input_bindings, substrate, weight_params)? {
+            return Ok((Some(db_weight), false))
+        }
+
+        if let Some((_, ident)) = weight_params.iter().find_by_name("actual") {
+            return Err(format_err_spanned!(
+                ident,
+                r#"`actual` requires the maximum weight to be charged via "dispatch", "call", "info", "expr", "max" or database weight attributes"#
+            ))
+        }
+
+        // Per-byte weights are handled separately by `handle_per_byte_weight`.
+        if ["per_input_byte", "per_output_byte"]
+            .into_iter()
+            .any(|name| weight_params.iter().find_by_name(name).is_some())
+        {
+            return Ok((None, false))
+        }
+
         Err(format_err_spanned!(
             weight_ident,
-            r#"either "dispatch" or "expr" attributes are expected"#
+            r#"either "dispatch", "call", "info", "expr", "reads", "writes", "base", "per_input_byte" or "per_output_byte" attributes are expected"#
         ))
     } else {
         Ok((None, false))
     }
 }
 
+/// Check whether the method weight depends on the method input, and thus can only be charged after reading it.
+fn weight_depends_on_input<'a, I: IntoIterator<Item = &'a NestedMeta>>(
+    input_bindings: &InputBindings,
+    iter: I,
+) -> bool {
+    let Some(weight_params) = find_weight_params(iter) else {
+        return false
+    };
+
+    if input_bindings.iter_raw_call_params().len() == 0 {
+        return false
+    }
+
+    // Pallet calls are built out of the method input, unless `args` are provided.
+    if weight_params.iter().find_by_name("dispatch").is_some() && weight_params.iter().find_by_name("args").is_none() {
+        return true
+    }
+
+    weight_params.iter().any(|param| {
+        match param {
+            // Actual weight is evaluated after the call, so it doesn't affect the charging order.
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(value),
+                ..
+            })) if !path.is_ident("actual") => {
+                value.parse::<TokenStream>().map_or(true, |tokens| {
+                    mentions_bindings(tokens, input_bindings.iter_raw_call_params())
+                })
+            }
+            _ => false,
+        }
+    })
+}
+
+/// Find the parameters of `weight` attribute.
+fn find_weight_params<'a, I: IntoIterator<Item = &'a NestedMeta>>(
+    iter: I,
+) -> Option<&'a Punctuated<NestedMeta, Token![,]>> {
+    iter.into_iter().find_map(|attr| {
+        let NestedMeta::Meta(Meta::List(list)) = attr else {
+            return None
+        };
+
+        list.path.is_ident("weight").then_some(&list.nested)
+    })
+}
+
+/// Handle `actual` weight parameter.
+///
+/// Returns an expression over the method's `result`, that evaluates to the actual weight
+/// the previously charged weight should be adjusted to.
+fn handle_actual_weight<'a, I: IntoIterator<Item = &'a NestedMeta>>(iter: I) -> Result<Option<Expr>, Error> {
+    let weight_params = find_weight_params(iter);
+
+    match weight_params.and_then(|params| params.iter().find_by_name("actual")) {
+        Some((LitOrPath::Lit(Lit::Str(expr)), _)) => Ok(Some(parse_str::<Expr>(&expr.value())?)),
+        Some((_, ident)) => {
+            Err(format_err_spanned!(
+                ident,
+                "`actual` attribute should contain an expression that returns `Weight`"
+            ))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Handle `per_input_byte` and `per_output_byte` weight parameters.
+///
+/// Returns an optional statement that charges weight based on the input length,
+/// and an expression of `Option<Weight>` type to be passed to `ChainExtensionEnvironment::write`.
+fn handle_per_byte_weight<'a, I: IntoIterator<Item = &'a NestedMeta>>(
+    iter: I,
+) -> Result<(Option<TokenStream>, TokenStream), Error> {
+    let weight_params = find_weight_params(iter);
+
+    let Some(weight_params) = weight_params else {
+        return Ok((None, quote! { None }))
+    };
+
+    let find_expr = |name| {
+        match weight_params.iter().find_by_name(name) {
+            Some((LitOrPath::Lit(Lit::Str(expr)), _)) => Ok(Some(parse_str::<Expr>(&expr.value())?)),
+            None => Ok(None),
+            Some((_, ident)) => {
+                Err(format_err_spanned!(
+                    ident,
+                    "`{}` attribute should contain an expression that returns `Weight`",
+                    name
+                ))
+            }
+        }
+    };
+
+    let input_charge = find_expr("per_input_byte")?.map(|per_input_byte| {
+        quote! {
+            env.charge_weight(
+                ::obce::substrate::frame_support::dispatch::Weight::saturating_mul(#per_input_byte, len as u64)
+            )?;
+        }
+    });
+
+    let output_weight = match find_expr("per_output_byte")? {
+        Some(per_output_byte) => quote! { Some(#per_output_byte) },
+        None => quote! { None },
+    };
+
+    Ok((input_charge, output_weight))
+}
+
+/// Handle database weight parameters (`reads`, `writes`, `base` and `proof_size`).
+///
+/// Returns [`None`] if neither `reads`, `writes` nor `base` were provided.
+fn handle_db_weight(
+    input_bindings: &InputBindings,
+    substrate: &GenericArgument,
+    weight_params: &Punctuated<NestedMeta, Token![,]>,
+) -> Result<Option<TokenStream>, Error> {
+    let find_expr = |name| {
+        match weight_params.iter().find_by_name(name) {
+            Some((LitOrPath::Lit(Lit::Str(expr)), _)) => parse_str::<Expr>(&expr.value()).map(Some),
+            Some((LitOrPath::Lit(Lit::Int(int)), _)) => Ok(Some(parse_quote! { #int })),
+            Some((_, ident)) => {
+                Err(format_err_spanned!(
+                    ident,
+                    "`{}` attribute should contain an integer or an expression",
+                    name
+                ))
+            }
+            None => Ok(None),
+        }
+    };
+
+    let reads = find_expr("reads")?;
+    let writes = find_expr("writes")?;
+    // Integer base weight is treated as reference time, since `Weight` can't be built from an integer directly.
+    let base = find_expr("base")?.map(|base| {
+        match base {
+            Expr::Lit(ExprLit { lit: Lit::Int(int), .. }) => {
+                parse_quote! { ::obce::substrate::frame_support::dispatch::Weight::from_parts(#int, 0) }
+            }
+            expr => expr,
+        }
+    });
+    let proof_size = find_expr("proof_size")?;
+
+    if reads.is_none() && writes.is_none() && base.is_none() {
+        return match weight_params.iter().find_by_name("proof_size") {
+            Some((_, ident)) => {
+                Err(format_err_spanned!(
+                    ident,
+                    "`proof_size` requires `reads` attribute to be provided"
+                ))
+            }
+            None => Ok(None),
+        }
+    }
+
+    let reads = reads.unwrap_or_else(|| parse_quote! { 0 });
+    let writes = writes.unwrap_or_else(|| parse_quote! { 0 });
+    let base = base.unwrap_or_else(|| parse_quote! { ::obce::substrate::frame_support::dispatch::Weight::zero() });
+    let proof_size = proof_size.unwrap_or_else(|| parse_quote! { 0 });
+
+    let raw_map = input_bindings.raw_special_mapping();
+
+    Ok(Some(quote! {{
+        #[allow(unused_variables)]
+        #raw_map
+        #[allow(clippy::unnecessary_cast)]
+        let (__reads, __writes, __proof_size) = ((#reads) as u64, (#writes) as u64, (#proof_size) as u64);
+        let __db_weight = <
+            <#substrate as ::obce::substrate::frame_system::Config>::DbWeight
+            as ::obce::substrate::frame_support::traits::Get<::obce::substrate::frame_support::weights::RuntimeDbWeight>
+        >::get();
+        let __weight = ::obce::substrate::frame_support::dispatch::Weight::saturating_add(#base, __db_weight.reads_writes(__reads, __writes))
+            .saturating_add(::obce::substrate::frame_support::dispatch::Weight::from_parts(
+                0,
+                __proof_size.saturating_mul(__reads),
+            ));
+        env.charge_weight(__weight)?
+    }}))
+}
+
 fn handle_expr_weight(input_bindings: &InputBindings, expr: &str, pre_charge: bool) -> Result<TokenStream, Error> {
     let expr = parse_str::<Expr>(expr)?;
 
@@ -464,6 +1288,7 @@ fn handle_expr_weight(input_bindings: &InputBindings, expr: &str, pre_charge: bo
 fn handle_dispatch_weight(
     ident: &Ident,
     input_bindings: &InputBindings,
+    substrate: &GenericArgument,
     dispatch_path: &str,
     args: Option<&str>,
 ) -> Result<TokenStream, Error> {
@@ -477,7 +1302,7 @@ fn handle_dispatch_weight(
         ))
     }
 
-    let (pallet_ns, _, method_name) = segments
+    let (pallet_ns, pallet, method_name) = segments
         .enumerate()
         .group_by(|(idx, _)| if *idx < segments_len - 2 { 0 } else { *idx })
         .into_iter()
@@ -500,13 +1325,65 @@ fn handle_dispatch_weight(
 
     let call_variant_name = format_ident!("new_call_variant_{}", method_name.last().unwrap().ident);
 
+    // Pallet generics (for example, `Pallet::<T, Instance1>`) are reused for the pallet's `Call`.
+    let call_generics = match &pallet.last().unwrap().arguments {
+        PathArguments::AngleBracketed(generics) => generics.args.to_token_stream(),
+        PathArguments::None => substrate.to_token_stream(),
+        PathArguments::Parenthesized(_) => {
+            return Err(format_err_spanned!(
+                ident,
+                "pallet generics should be provided in angle brackets"
+            ))
+        }
+    };
+
+    let raw_map = input_bindings.raw_special_mapping();
+
+    Ok(quote! {{
+        #[allow(unused_variables)]
+        #raw_map
+        let __call_variant = &#pallet_ns ::Call::<#call_generics>::#call_variant_name(#dispatch_args);
+        let __dispatch_info = <#pallet_ns ::Call<#call_generics> as ::obce::substrate::frame_support::dispatch::GetDispatchInfo>::get_dispatch_info(__call_variant);
+        env.charge_weight(__dispatch_info.weight)?
+    }})
+}
+
+fn handle_info_weight(
+    input_bindings: &InputBindings,
+    method_name: &Ident,
+    (weight_info, weight_info_trait): (&Type, &Ident),
+    components: Option<&str>,
+) -> Result<TokenStream, Error> {
+    let components = if let Some(components) = components {
+        let parser = Punctuated::<Expr, Token![,]>::parse_terminated;
+        parser.parse_str(components)?.to_token_stream()
+    } else {
+        quote! {}
+    };
+
+    let raw_map = input_bindings.raw_special_mapping();
+
+    Ok(quote! {{
+        #[allow(unused_variables)]
+        #raw_map
+        env.charge_weight(<#weight_info as #weight_info_trait>::#method_name(#components))?
+    }})
+}
+
+fn handle_call_weight(
+    input_bindings: &InputBindings,
+    substrate: &GenericArgument,
+    call: &str,
+) -> Result<TokenStream, Error> {
+    let call = parse_str::<Expr>(call)?;
+
     let raw_map = input_bindings.raw_special_mapping();
 
     Ok(quote! {{
         #[allow(unused_variables)]
         #raw_map
-        let __call_variant = &#pallet_ns ::Call::<T>::#call_variant_name(#dispatch_args);
-        let __dispatch_info = <#pallet_ns ::Call<T> as ::obce::substrate::frame_support::dispatch::GetDispatchInfo>::get_dispatch_info(__call_variant);
+        let __runtime_call: <#substrate as ::obce::substrate::pallet_contracts::Config>::RuntimeCall = ::core::convert::Into::into(#call);
+        let __dispatch_info = ::obce::substrate::frame_support::dispatch::GetDispatchInfo::get_dispatch_info(&__runtime_call);
         env.charge_weight(__dispatch_info.weight)?
     }})
 }
@@ -26,4 +26,5 @@ pub mod id;
 pub mod implementation;
 pub mod mock;
 mod types;
+pub mod unique_ids;
 mod utils;
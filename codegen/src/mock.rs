@@ -7,24 +7,46 @@ use syn::{
     parse2,
     parse_quote,
     Error,
+    FnArg,
+    Ident,
     ImplItem,
+    ImplItemMethod,
+    ImplItemType,
     ItemImpl,
     ItemTrait,
+    Lit,
+    ReturnType,
     TraitItem,
     TraitItemMethod,
+    Type,
+    TypeGenerics,
 };
 
 use crate::{
     format_err_spanned,
+    types::AttributeArgs,
     utils::{
         into_u32,
+        replace_assoc_types,
         InputBindings,
+        LitOrPath,
+        MetaUtils,
     },
 };
 
-pub fn generate(_: TokenStream, input: TokenStream) -> Result<TokenStream, Error> {
+pub fn generate(attrs: TokenStream, input: TokenStream) -> Result<TokenStream, Error> {
     let mut impl_item: ItemImpl = parse2(input)?;
 
+    let attrs = parse2::<AttributeArgs>(attrs)?;
+
+    let registration_name = match attrs.iter().find_by_name("registration") {
+        Some((LitOrPath::Lit(Lit::Str(name)), _)) => Some(name.parse::<Ident>()?),
+        Some((_, ident)) => return Err(format_err_spanned!(ident, "registration should be a string")),
+        None => None,
+    };
+
+    let registry_only = matches!(attrs.iter().find_by_name("registry_only"), Some((LitOrPath::Path, _)));
+
     let Some((_, trait_name, _)) = impl_item.trait_ else {
         return Err(format_err_spanned!(
             impl_item,
@@ -35,7 +57,19 @@ pub fn generate(_: TokenStream, input: TokenStream) -> Result<TokenStream, Error
 
     let (impls, types, where_clause) = impl_item.generics.split_for_impl();
 
-    // We assume that every single item is a method.
+    let assoc_types = impl_item
+        .items
+        .iter()
+        .filter_map(|item| {
+            if let ImplItem::Type(type_item) = item {
+                Some(type_item.clone())
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // We assume that every other item is a method.
     let methods = impl_item
         .items
         .iter_mut()
@@ -53,17 +87,24 @@ pub fn generate(_: TokenStream, input: TokenStream) -> Result<TokenStream, Error
     };
 
     mock_trait.generics = impl_item.generics.clone();
-    mock_trait.items = methods
+    mock_trait.items = assoc_types
         .iter()
-        .map(|method| (**method).clone())
-        .map(|val| {
+        .map(|type_item| {
+            let ImplItemType { attrs, ident, .. } = type_item;
+
+            TraitItem::Type(parse_quote! {
+                #(#attrs)*
+                type #ident;
+            })
+        })
+        .chain(methods.iter().map(|method| (**method).clone()).map(|val| {
             TraitItem::Method(TraitItemMethod {
                 attrs: val.attrs,
                 sig: val.sig,
                 default: None,
                 semi_token: None,
             })
-        })
+        }))
         .collect();
 
     let mut mock_impl: ItemImpl = parse_quote! {
@@ -71,89 +112,194 @@ pub fn generate(_: TokenStream, input: TokenStream) -> Result<TokenStream, Error
     };
 
     mock_impl.generics = impl_item.generics.clone();
-    mock_impl.items = methods
+    mock_impl.items = assoc_types
         .iter()
-        .map(|method| (**method).clone())
-        .map(ImplItem::Method)
+        .cloned()
+        .map(ImplItem::Type)
+        .chain(methods.iter().map(|method| (**method).clone()).map(ImplItem::Method))
         .collect();
 
-    let proxies = methods.iter()
-        .map(|method| {
-            let hash = into_u32(&method.sig.ident);
+    let proxies = methods.iter().map(|method| {
+        let hash = into_u32(&method.sig.ident);
+
+        let method_name = &method.sig.ident;
+        let proxy_name = format_ident!("ProxyFor{}", hash);
+
+        let (input_ty, output_ty) = method_types(method, &item, &types).unwrap_or_else(|| {
+            (
+                parse_quote! { <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::Input },
+                parse_quote! { <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::Output },
+            )
+        });
 
-            let method_name = &method.sig.ident;
-            let proxy_name = format_ident!("ProxyFor{}", hash);
-            let proxy_where_clause = if let Some(mut where_clause) = where_clause.cloned() {
-                where_clause.predicates.push(parse_quote! {
+        let proxy_where_clause = if let Some(mut where_clause) = where_clause.cloned() {
+            where_clause.predicates.push(parse_quote! {
+                dyn #trait_name: ::obce::codegen::ExtensionDescription,
+            });
+            where_clause.predicates.push(parse_quote! {
+                #output_ty: ::scale::Encode,
+            });
+            where_clause.predicates.push(parse_quote! {
+                #input_ty: ::scale::Decode
+            });
+            where_clause
+        } else {
+            parse_quote! {
+                where
                     dyn #trait_name: ::obce::codegen::ExtensionDescription,
-                });
-                where_clause.predicates.push(parse_quote! {
-                    <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::Output: ::scale::Encode,
-                });
-                where_clause.predicates.push(parse_quote! {
-                    <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::Input: ::scale::Decode
-                });
-                where_clause
-            } else {
-                parse_quote! {
-                    where
-                        dyn #trait_name: ::obce::codegen::ExtensionDescription,
-                        <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::Output: ::scale::Encode,
-                        <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::Input: ::scale::Decode
-                }
-            };
+                    #output_ty: ::scale::Encode,
+                    #input_ty: ::scale::Decode
+            }
+        };
 
-            let input_bindings = InputBindings::from_iter(&method.sig.inputs);
-            let lhs_pat = input_bindings.lhs_pat(Some(parse_quote! {
-                <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::Input
-            }));
-            let call_params = input_bindings.iter_call_params();
+        let input_bindings = InputBindings::from_iter(&method.sig.inputs);
+        let lhs_pat = input_bindings.lhs_pat(Some(input_ty));
+        let call_params = input_bindings.iter_call_params();
 
-            quote! {
-                struct #proxy_name #types (::std::rc::Rc<::std::cell::RefCell<#item>>);
+        quote! {
+            struct #proxy_name #types (::obce::ink_lang::mock::MockHandle<#item>);
 
-                impl #impls ::obce::ink_lang::env::test::ChainExtension for #proxy_name #types #proxy_where_clause {
-                    fn func_id(&self) -> u32 {
-                        let trait_id = <dyn #trait_name as ::obce::codegen::ExtensionDescription>::ID;
-                        let func_id = <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::ID;
-                        (trait_id as u32) << 16 | (func_id as u32)
-                    }
+            impl #impls ::obce::ink_lang::env::test::ChainExtension for #proxy_name #types #proxy_where_clause {
+                fn func_id(&self) -> u32 {
+                    let trait_id = <dyn #trait_name as ::obce::codegen::ExtensionDescription>::ID;
+                    let func_id = <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::ID;
+                    (trait_id as u32) << 16 | (func_id as u32)
+                }
 
-                    fn call(&mut self, mut input: &[u8], output: &mut Vec<u8>) -> u32 {
-                        let context = &mut *self.0.borrow_mut();
+                fn call(&mut self, mut input: &[u8], output: &mut Vec<u8>) -> u32 {
+                    let bytes: Vec<u8> = ::scale::Decode::decode(&mut &input[..])
+                        .unwrap();
 
-                        let bytes: Vec<u8> = ::scale::Decode::decode(&mut &input[..])
-                            .unwrap();
+                    self.0.record(::obce::ink_lang::mock::MockCall {
+                        method: ::core::stringify!(#method_name),
+                        func_id: <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::ID,
+                        input: bytes.clone(),
+                    });
 
-                        let #lhs_pat = ::scale::Decode::decode(&mut &bytes[..])
-                            .unwrap();
+                    let context = &mut *self.0.context_mut();
 
-                        #[allow(clippy::unnecessary_mut_passed)]
-                        let call_output: <dyn #trait_name as ::obce::codegen::MethodDescription<#hash>>::Output = <#item as MockTrait #types>::#method_name(
+                    let #lhs_pat = ::scale::Decode::decode(&mut &bytes[..])
+                        .unwrap();
+
+                    #[allow(clippy::unnecessary_mut_passed)]
+                    let call_output = ::obce::ink_lang::mock::IntoMockResult::<#output_ty>::into_mock_result(
+                        <#item as MockTrait #types>::#method_name(
                             context
                             #(, #call_params)*
-                        );
-
-                        ::scale::Encode::encode_to(&call_output, output);
+                        )
+                    );
 
-                        0
-                    }
+                    call_output.into_status_code(output)
                 }
+            }
+        }
+    });
+
+    let proxy_names = methods
+        .iter()
+        .map(|method| format_ident!("ProxyFor{}", into_u32(&method.sig.ident)));
+
+    let trait_ident = &trait_name
+        .segments
+        .last()
+        .expect("trait path should have at least one segment")
+        .ident;
+    // Mocks with explicitly specified registration names are new code, and thus don't need
+    // the legacy registration function, which would otherwise clash with other mocks in the same module.
+    let legacy_register_fn = !registry_only && registration_name.is_none();
 
-                ::obce::ink_lang::env::test::register_chain_extension(#proxy_name(wrapped_context.clone()));
+    // Mocks of the same trait with different contexts should have their registration names specified explicitly.
+    let registration_name = registration_name.unwrap_or_else(|| format_ident!("{}Registration", trait_ident));
+    let registration_doc = format!("Registration of the mocked `{trait_ident}` chain extension.");
+
+    let register_fn = legacy_register_fn.then(|| {
+        let deprecation_note = format!("use `{registration_name}::register` instead");
+        let turbofish = types.as_turbofish();
+
+        quote! {
+            #[deprecated(note = #deprecation_note)]
+            pub fn register_chain_extensions #impls (ctx: #item) -> ::obce::ink_lang::mock::MockHandle<#item> #where_clause {
+                #registration_name #turbofish::register(ctx)
             }
-        });
+        }
+    });
 
     Ok(quote! {
-        pub fn register_chain_extensions #types (ctx: #item) {
-            #[allow(unused_variables)]
-            let wrapped_context = ::std::rc::Rc::new(::std::cell::RefCell::new(ctx));
+        #[doc = #registration_doc]
+        pub struct #registration_name #impls (::core::marker::PhantomData<fn() -> #item>) #where_clause;
 
-            #mock_trait
+        impl #impls ::obce::ink_lang::mock::MockExtension for #registration_name #types #where_clause {
+            type Extension = dyn #trait_name;
 
-            #mock_impl
+            type Context = #item;
 
-            #(#proxies)*
+            fn extensions(
+                handle: &::obce::ink_lang::mock::MockHandle<#item>,
+            ) -> Vec<Box<dyn ::obce::ink_lang::env::test::ChainExtension>> {
+                #mock_trait
+
+                #mock_impl
+
+                #(#proxies)*
+
+                vec![#(Box::new(#proxy_names(handle.clone())) as Box<dyn ::obce::ink_lang::env::test::ChainExtension>),*]
+            }
         }
+
+        impl #impls #registration_name #types #where_clause {
+            /// Register the mock with the provided context, replacing previously registered chain extensions
+            /// with the same function identifiers.
+            pub fn register(ctx: #item) -> ::obce::ink_lang::mock::MockHandle<#item> {
+                ::obce::ink_lang::mock::register::<Self>(ctx)
+            }
+        }
+
+        #register_fn
+    })
+}
+
+/// Get input and output types of a mocked method, if it uses associated types.
+///
+/// Associated types are not present in [`MethodDescription`] types, so in that case we take the types
+/// from the method signature, resolving associated types using the mock itself.
+fn method_types(method: &ImplItemMethod, item: &Type, types: &TypeGenerics) -> Option<(Type, Type)> {
+    let mut has_assoc_types = false;
+
+    let mut resolve = |mut ty: Type| {
+        has_assoc_types |= replace_assoc_types(&mut ty, |ident| {
+            parse_quote! {
+                <#item as MockTrait #types>::#ident
+            }
+        });
+
+        ty
+    };
+
+    let input_tys: Vec<_> = method
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|input| {
+            if let FnArg::Typed(pat) = input {
+                Some(resolve((*pat.ty).clone()))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let output_ty = if let ReturnType::Type(_, ty) = &method.sig.output {
+        resolve((**ty).clone())
+    } else {
+        parse_quote! { () }
+    };
+
+    has_assoc_types.then(|| {
+        (
+            parse_quote! {
+                (#(#input_tys),*)
+            },
+            output_ty,
+        )
     })
 }
@@ -0,0 +1,73 @@
+use itertools::Itertools;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::Parser,
+    punctuated::Punctuated,
+    Error,
+    Token,
+    Type,
+    TypeParamBound,
+};
+
+use crate::format_err_spanned;
+
+pub fn generate(input: TokenStream) -> Result<TokenStream, Error> {
+    let extensions = Punctuated::<Type, Token![,]>::parse_terminated.parse2(input)?;
+
+    let extensions: Vec<_> = extensions
+        .iter()
+        .map(|ty| {
+            let Type::TraitObject(trait_object) = ty else {
+                return Err(format_err_spanned!(
+                    ty,
+                    "expected chain extension trait object (for example, `dyn Trait`)"
+                ))
+            };
+
+            let Some(TypeParamBound::Trait(bound)) = trait_object.bounds.first() else {
+                return Err(format_err_spanned!(
+                    ty,
+                    "expected chain extension trait object (for example, `dyn Trait`)"
+                ))
+            };
+
+            let name = bound.path.segments.iter().map(|segment| &segment.ident).join("::");
+
+            Ok((ty, name))
+        })
+        .try_collect()?;
+
+    let method_checks = extensions.iter().map(|(ty, name)| {
+        let message = format!("chain extension `{name}` has duplicated method identifiers");
+
+        quote! {
+            ::obce::codegen::assert_unique_method_ids(
+                <#ty as ::obce::codegen::ExtensionDescription>::METHOD_IDS,
+                #message,
+            );
+        }
+    });
+
+    let extension_checks = extensions
+        .iter()
+        .tuple_combinations()
+        .map(|((lhs_ty, lhs_name), (rhs_ty, rhs_name))| {
+            let message = format!("chain extensions `{lhs_name}` and `{rhs_name}` have the same identifier");
+
+            quote! {
+                ::core::assert!(
+                    <#lhs_ty as ::obce::codegen::ExtensionDescription>::ID
+                        != <#rhs_ty as ::obce::codegen::ExtensionDescription>::ID,
+                    #message,
+                );
+            }
+        });
+
+    Ok(quote! {
+        const _: () = {
+            #(#method_checks)*
+            #(#extension_checks)*
+        };
+    })
+}
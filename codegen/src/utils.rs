@@ -22,12 +22,20 @@
 use std::borrow::Borrow;
 
 use itertools::Itertools;
-use proc_macro2::TokenStream;
+use proc_macro2::{
+    TokenStream,
+    TokenTree,
+};
 use quote::{
     format_ident,
     quote,
+    ToTokens,
 };
 use syn::{
+    visit_mut::{
+        self,
+        VisitMut,
+    },
     Attribute,
     FnArg,
     Ident,
@@ -37,6 +45,7 @@ use syn::{
     Pat,
     PatType,
     Type,
+    TypePath,
 };
 
 use crate::types::AttributeArgs;
@@ -137,6 +146,78 @@ where
     }
 }
 
+/// Replace associated type paths (for example, `Self::Balance`) inside of the provided type.
+///
+/// Returns `true` if any associated type was replaced.
+pub fn replace_assoc_types<F: FnMut(&Ident) -> Type>(ty: &mut Type, replacement: F) -> bool {
+    let mut replacer = AssocTypeReplacer {
+        replacement,
+        replaced: false,
+    };
+    replacer.visit_type_mut(ty);
+    replacer.replaced
+}
+
+/// Check whether the provided type mentions `Self` (for example, `Self::Balance`).
+pub fn mentions_self(ty: &Type) -> bool {
+    fn visit(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|token| {
+            match token {
+                TokenTree::Ident(ident) => ident == "Self",
+                TokenTree::Group(group) => visit(group.stream()),
+                _ => false,
+            }
+        })
+    }
+
+    visit(ty.to_token_stream())
+}
+
+/// Check whether the provided tokens mention any identifier bound by the provided patterns.
+pub fn mentions_bindings<'a, I: IntoIterator<Item = &'a Pat>>(tokens: TokenStream, patterns: I) -> bool {
+    fn collect(tokens: TokenStream, idents: &mut Vec<String>) {
+        for token in tokens {
+            match token {
+                TokenTree::Ident(ident) if ident != "mut" && ident != "ref" => idents.push(ident.to_string()),
+                TokenTree::Group(group) => collect(group.stream(), idents),
+                _ => {}
+            }
+        }
+    }
+
+    let mut bindings = vec![];
+
+    for pattern in patterns {
+        collect(pattern.to_token_stream(), &mut bindings);
+    }
+
+    let mut mentioned = vec![];
+    collect(tokens, &mut mentioned);
+
+    mentioned.iter().any(|ident| bindings.contains(ident))
+}
+
+struct AssocTypeReplacer<F> {
+    replacement: F,
+    replaced: bool,
+}
+
+impl<F: FnMut(&Ident) -> Type> VisitMut for AssocTypeReplacer<F> {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(TypePath { qself: None, path }) = ty {
+            if let Some((self_segment, assoc_segment)) = path.segments.iter().collect_tuple() {
+                if self_segment.ident == "Self" && self_segment.arguments.is_empty() {
+                    *ty = (self.replacement)(&assoc_segment.ident);
+                    self.replaced = true;
+                    return
+                }
+            }
+        }
+
+        visit_mut::visit_type_mut(self, ty);
+    }
+}
+
 pub struct InputBindings<'a> {
     bindings: Vec<&'a PatType>,
 }
@@ -226,9 +307,15 @@ mod tests {
         FnArg,
         Stmt,
         Token,
+        Type,
     };
 
-    use super::InputBindings;
+    use super::{
+        mentions_bindings,
+        mentions_self,
+        replace_assoc_types,
+        InputBindings,
+    };
 
     #[test]
     fn special_bindings_conversion() {
@@ -252,6 +339,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn assoc_types_replacement() {
+        let mut ty: Type = parse_quote! {
+            (Self::Balance, Vec<Self::AccountId>, <T as Config>::Balance)
+        };
+
+        assert!(replace_assoc_types(&mut ty, |ident| parse_quote! { Resolved<#ident> }));
+
+        assert_eq!(
+            ty,
+            parse_quote! {
+                (Resolved<Balance>, Vec<Resolved<AccountId> >, <T as Config>::Balance)
+            }
+        );
+
+        let mut ty: Type = parse_quote! { (u32, u64) };
+        assert!(!replace_assoc_types(&mut ty, |ident| parse_quote! { Resolved<#ident> }));
+    }
+
+    #[test]
+    fn self_mentions() {
+        assert!(mentions_self(&parse_quote! { Vec<Self::Balance> }));
+        assert!(mentions_self(&parse_quote! { <Self as Trait>::Balance }));
+        assert!(!mentions_self(&parse_quote! { (u32, <T as Config>::Balance) }));
+    }
+
+    #[test]
+    fn binding_mentions() {
+        let parser = Punctuated::<FnArg, Token![,]>::parse_terminated;
+
+        let fn_args = parser
+            .parse2(quote! {
+                mut one: u32, (two, three): (u32, u32)
+            })
+            .unwrap();
+
+        let input_bindings = InputBindings::from_iter(&fn_args);
+
+        assert!(mentions_bindings(
+            quote! { Weight::from_parts(one as u64, 0) },
+            input_bindings.iter_raw_call_params()
+        ));
+        assert!(mentions_bindings(
+            quote! { three.len() },
+            input_bindings.iter_raw_call_params()
+        ));
+        assert!(!mentions_bindings(
+            quote! { Weight::from_parts(100, 0) },
+            input_bindings.iter_raw_call_params()
+        ));
+    }
+
     #[test]
     fn raw_special_mapping_empty() {
         let input_bindings = InputBindings::from_iter(iter::empty());
@@ -83,7 +83,7 @@ mod test_contract {
 
         #[ink::test]
         fn update_works() {
-            register_chain_extensions(Context);
+            RandExtensionRegistration::register(Context);
             let mut contract = TestContract::new_default();
             contract.update([1; 32]).unwrap();
             assert_eq!(contract.get(), [1; 32]);
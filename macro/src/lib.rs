@@ -30,6 +30,7 @@ use obce_codegen::{
     id,
     implementation,
     mock,
+    unique_ids,
 };
 
 /// Chain extension definition for use with Substrate-based nodes and ink! smart contracts.
@@ -60,6 +61,244 @@ use obce_codegen::{
 /// correspondingly.
 ///
 /// `id` accepts literals of type [`&str`] and [`u16`].
+///
+/// # Identifier schemes
+///
+/// Identifiers that weren't specified explicitly are derived using the identifier scheme,
+/// that can be changed via `#[obce::definition(id_scheme = ...)]`:
+///
+/// * `"name"` (default) hashes the bare trait and method names.
+/// * `"path"` hashes the `crate::Trait` and `crate::Trait::method` paths, where `crate` is the name
+///   of the crate that contains the definition (as provided by Cargo). Module paths are not available
+///   to macros, so traits with the same name in different modules of the same crate still collide.
+/// * `"namespace"` hashes the `namespace::Trait` and `namespace::method` paths, where namespace
+///   is provided via `#[obce::definition(id_scheme = "namespace", namespace = "...")]`.
+/// * `"explicit"` requires all identifiers to be specified explicitly.
+///
+/// The chosen scheme is available via `ExtensionDescription::ID_SCHEME`,
+/// so that external tools can recompute identifiers.
+///
+/// # Strict ABI
+///
+/// Every method description exposes a `SIGNATURE_HASH`, that is computed from the method input
+/// and output types. With `#[obce::definition(strict_abi)]` the signature hash is folded into
+/// method identifiers, so changing a method signature (for example, from `fn f(&self, a: u32)`
+/// to `fn f(&self, a: u64)`) changes its identifier as well.
+///
+/// Contracts that were built using an outdated signature then call a method that is unknown to the
+/// runtime, which traps the contract (or returns `CallError::UnknownFunction` from `try_*` methods,
+/// see [Unknown methods](macro@implementation#unknown-methods)) instead of silently misinterpreting
+/// the data.
+///
+/// Since method identifiers are derived from method signatures, strict ABI mode can't be used
+/// together with `#[obce(id = ...)]` method attributes or the `explicit` identifier scheme.
+/// If a derived identifier is reserved or collides with another method, you can change it
+/// by specifying a salt, that is folded into the identifier as well:
+///
+/// ```ignore
+/// #[obce::definition(strict_abi)]
+/// pub trait ChainExtensionDefinition {
+///     #[obce(salt = 1)]
+///     fn some_method(&self, val: u32) -> u64;
+/// }
+/// ```
+///
+/// The signature check is syntactic, since types can't be resolved by macros. Primitive and prelude types
+/// are hashed by their names (`Vec<u8>` and `::std::vec::Vec<u8>` are equal), while other types are hashed
+/// by the paths they are spelled with (`a::Foo` and `b::Foo` are different). Thus the same type spelled
+/// using different paths or type aliases hashes differently, and changes inside of user-defined types
+/// (such as a new struct field) aren't detected.
+///
+/// Only the lower 16 bits of a signature hash are folded into the method identifier, so a changed signature
+/// may still map to the identifier of the previous one, and methods with different signatures may collide.
+/// Collisions within a single definition are reported as duplicated method identifiers and can be
+/// resolved using a salt.
+/// Method aliases are not affected by strict ABI mode, though they're a part of the ABI hash.
+///
+/// # Capability handshake
+///
+/// Method identifiers in the `0xFFF0..=0xFFFF` range are reserved, and chain extensions implemented with
+/// [`#[obce::implementation]`](macro@implementation) answer them with the chain extension version,
+/// supported method identifiers and the ABI hash. With `ink` feature enabled, you can use them
+/// to detect supported features before calling a chain extension:
+///
+/// ```ignore
+/// if obce::ink_lang::is_method_supported::<dyn ChainExtensionDefinition>(
+///     obce::id!(ChainExtensionDefinition::some_method)
+/// )? {
+///     Extension.some_method(123);
+/// }
+/// ```
+///
+/// See `obce::ink_lang::{extension_version, supported_methods, is_method_supported, abi_hash}` for more details.
+///
+/// # Error codes
+///
+/// Methods that return `Result<T, E>`, where `E` is an [`#[obce::error]`](macro@error) type,
+/// can be marked with `#[obce(ret_val)]` to decode the status code returned by the
+/// Substrate side of a chain extension:
+///
+/// ```ignore
+/// #[obce::error]
+/// pub enum Error {
+///     #[obce(ret_val = "100")]
+///     One,
+/// }
+///
+/// #[obce::definition]
+/// pub trait ChainExtensionDefinition {
+///     #[obce(ret_val)]
+///     fn some_method(&self, argument: u32) -> Result<u64, Error>;
+/// }
+/// ```
+///
+/// With `ink` feature enabled, non-zero status codes are converted back into the
+/// corresponding error variants instead of being ignored. The implementation method
+/// should be marked with `#[obce(ret_val)]` as well to produce these status codes,
+/// which is checked at compile time using `MethodDescription::RET_VAL`.
+///
+/// # Non-trapping calls
+///
+/// With `ink` feature enabled, every method also receives a `try_*` counterpart,
+/// that returns `Result<Output, obce::ink_lang::CallError>` instead of trapping the contract
+/// when the call can't be completed:
+///
+/// ```ignore
+/// #[obce::definition]
+/// pub trait ChainExtensionDefinition {
+///     fn some_method(&self, argument: u32) -> u64;
+/// }
+///
+/// // Generated by `#[obce::definition]`:
+/// // fn try_some_method(&self, argument: u32) -> Result<u64, obce::ink_lang::CallError>;
+/// ```
+///
+/// `CallError` distinguishes between output decoding failures, methods that are unknown to the runtime
+/// and unhandled status codes. Status codes of methods marked with `#[obce(ret_val)]` are still
+/// converted into the corresponding error variants when possible.
+///
+/// Calls to unknown methods are reported as `CallError::UnknownFunction`, unless the chain extension is implemented
+/// with `#[obce::implementation(trap_unknown_functions)]` (see [Unknown methods](macro@implementation#unknown-methods))
+/// or with an older OBCE version, in which case such calls trap the contract.
+/// Note that calls to a chain extension that is not registered in the runtime at all
+/// trap the contract before the extension gets a chance to reply.
+///
+/// # Helper methods
+///
+/// Methods with default implementation are treated as helpers, that are
+/// available only on the ink! side and are built out of other chain extension calls:
+///
+/// ```ignore
+/// #[obce::definition]
+/// pub trait ChainExtensionDefinition {
+///     fn balance_of(&self, account: [u8; 32]) -> u128;
+///
+///     fn total_balance_of(&self, accounts: [[u8; 32]; 2]) -> u128 {
+///         self.balance_of(accounts[0]) + self.balance_of(accounts[1])
+///     }
+/// }
+/// ```
+///
+/// Helper methods have no identifiers of their own, can't be marked with OBCE attributes,
+/// and don't require an implementation on the Substrate side.
+///
+/// # Associated types and constants
+///
+/// Chain extension definitions may contain associated types and associated constants:
+///
+/// ```ignore
+/// #[obce::definition]
+/// pub trait ChainExtensionDefinition {
+///     type Balance;
+///
+///     const MAX_BATCH: u32 = 16;
+///
+///     fn balance_of(&self, account: [u8; 32]) -> Self::Balance;
+/// }
+/// ```
+///
+/// Associated types are not resolved automatically. Instead, each side of a chain extension
+/// specifies them in its implementation, usually using the ink! `Environment` in contracts
+/// and the runtime configuration on the Substrate side:
+///
+/// ```ignore
+/// // ink! side
+/// impl ChainExtensionDefinition for Extension {
+///     type Balance = <ink::env::DefaultEnvironment as ink::env::Environment>::Balance;
+/// }
+///
+/// // Substrate side
+/// #[obce::implementation]
+/// impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, Extension>
+/// where
+///     T: SysConfig + ContractConfig + pallet_balances::Config,
+///     <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+///     Env: ChainExtensionEnvironment<E, T>,
+/// {
+///     type Balance = <T as pallet_balances::Config>::Balance;
+///
+///     fn balance_of(&self, account: [u8; 32]) -> Self::Balance {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// Associated types are automatically bounded by `scale::Codec`, and the encoded representations
+/// of both sides should match. Since associated types are not available on trait objects,
+/// methods that use them are bounded by `Self: Sized`.
+///
+/// Associated constants are shared by both sides of a chain extension, thus they should always have a value.
+/// They are accessible as `<dyn ChainExtensionDefinition>::MAX_BATCH`.
+///
+/// # Versioning
+///
+/// You can use `#[obce::definition(version = ...)]` to specify the current version of your
+/// chain extension (`1` by default), and the following method attributes to evolve it
+/// without breaking already deployed contracts:
+///
+/// ```ignore
+/// #[obce::definition(version = 2)]
+/// pub trait ChainExtensionDefinition {
+///     #[obce(id = 2, alias_id = 1)]
+///     fn balance_of(&self, account: [u8; 32]) -> u128;
+///
+///     #[obce(since = 2)]
+///     fn total_supply(&self) -> u128;
+///
+///     #[obce(deprecated = "use `balance_of` instead")]
+///     fn free_balance_of(&self, account: [u8; 32]) -> u128;
+/// }
+/// ```
+///
+/// * `alias_id` routes additional method identifiers to the same implementation on the Substrate side.
+///   It accepts the same literals as `id`, and can be specified multiple times.
+/// * `since` documents the chain extension version in which the method was introduced,
+///   and can't be newer than the chain extension version itself. The version is available
+///   via `MethodDescription::SINCE` (`1` for methods without `since`).
+/// * `deprecated` marks the method as deprecated on the ink! side, with an optional note.
+///
+/// # Round-trip tests
+///
+/// Method inputs are encoded by the ink! glue code and decoded by the Substrate dispatcher, which are
+/// generated separately. With `#[obce::definition(proptest)]` OBCE generates a [`proptest`](https://docs.rs/proptest)
+/// test for every method, that encodes arbitrary inputs the same way ink! does, decodes them the same way
+/// the dispatcher does, and checks that the decoded values (as well as the method output) match the original ones:
+///
+/// ```ignore
+/// #[obce::definition(proptest)]
+/// pub trait ChainExtensionDefinition {
+///     fn balance_of(&self, account: [u8; 32]) -> u128;
+///
+///     fn transfer(&mut self, to: [u8; 32], value: u128) -> Result<(), u8>;
+/// }
+/// ```
+///
+/// The tests are generated inside of a `#[cfg(test)]` module, and require `proptest` to be
+/// available as a (dev-)dependency. Method input and output types should implement
+/// `proptest::arbitrary::Arbitrary`, `Clone`, `PartialEq` and `Debug`.
+///
+/// Round-trip tests can't be generated for generic definitions, as well as for methods that use
+/// associated types, since these types are resolved separately by each side of a chain extension.
 #[proc_macro_attribute]
 pub fn definition(attrs: TokenStream, trait_item: TokenStream) -> TokenStream {
     match definition::generate(attrs.into(), trait_item.into()) {
@@ -135,13 +374,83 @@ pub fn definition(attrs: TokenStream, trait_item: TokenStream) -> TokenStream {
 /// arguments are different, you can use `args` to override them:
 /// `#[obce(weight(dispatch = "pallet_example::Pallet::<T>::my_call", args = "some_val,123"))]`.
 ///
+/// Generic arguments of the pallet are used for its call as well, so instanced pallets are supported:
+/// `#[obce(weight(dispatch = "pallet_assets::Pallet::<T, Instance1>::transfer"))]`.
+///
+/// Alternatively, you can build a call by yourself with `call`. The provided expression
+/// is converted into `pallet_contracts::Config::RuntimeCall`, and the weight is obtained from
+/// its dispatch information:
+/// `#[obce(weight(call = "pallet_example::Call::<T>::my_call { value: *some_val }"))]`.
+///
 /// You can also use `#[obce(weight(expr = ...))]` to charge weight without pallet calls.
 /// In this case, you can simply provide any expression which returns `Weight`:
 /// `#[obce(weight(expr = "Weight::from_parts(ref_time, proof_size)"))]`.
 ///
+/// Storage-bound methods can be charged using the runtime's `DbWeight` with `reads`, `writes` and `base`
+/// (either integers or expressions over the method arguments). An integer `base` is charged as reference time,
+/// while a `base` expression should return `Weight`. `proof_size` sets the estimated
+/// proof size of a single read, so that both weight dimensions are charged:
+/// `#[obce(weight(reads = "keys.len()", writes = 1, base = "Weight::from_parts(10_000, 0)", proof_size = 128))]`.
+///
+/// Methods that accept or return variable-sized data can be charged per byte
+/// with `per_input_byte` and `per_output_byte`, either on their own or together with
+/// `dispatch`/`expr`:
+/// `#[obce(weight(expr = "Weight::from_parts(100, 0)", per_input_byte = "Weight::from_parts(10, 1)"))]`.
+///
+/// `per_input_byte` is multiplied by the input length and charged before the input is decoded,
+/// while `per_output_byte` is passed to `ChainExtensionEnvironment::write`,
+/// which charges it for each byte written to the contract's output buffer.
+/// Since neither of them has access to the decoded input,
+/// both expressions must not refer to the method arguments.
+///
+/// When the real cost of a call is known only after its execution, you can charge the worst-case
+/// weight first and refund the difference afterwards by providing the `actual` expression, which
+/// has access to the method's `result` by reference:
+///
+/// ```ignore
+/// #[obce(weight(
+///     max = "Weight::from_parts(1_000 * items as u64, 0)",
+///     actual = "Weight::from_parts(1_000 * *result as u64, 0)"
+/// ))]
+/// fn process(&mut self, items: u32) -> u32 {
+///     // Returns the number of items that were actually processed.
+///     items / 2
+/// }
+/// ```
+///
+/// `max` is an alias for `expr`, and `actual` works with `dispatch` weights as well.
+/// The charged weight is adjusted before the result is converted into a status code, however
+/// the weight is never increased and no refunds are made in case of critical errors.
+///
+/// Methods that learn their cost while running can charge weight incrementally with
+/// `ExtensionContext::charge`, and give back the unused part with `ExtensionContext::refund`.
+/// `ExtensionContext` keeps the charged amount handles on its own, and tracks
+/// the running total that is available via `charged_so_far`:
+///
+/// ```ignore
+/// fn sum(&mut self, items: Vec<u32>) -> Result<u64, DispatchError> {
+///     let mut sum = 0;
+///
+///     for item in items {
+///         if self.remaining().any_lt(Weight::from_parts(2_000, 0)) {
+///             break
+///         }
+///
+///         self.charge(Weight::from_parts(2_000, 0))?;
+///         sum += item as u64;
+///     }
+///
+///     Ok(sum)
+/// }
+/// ```
+///
+/// Weights that don't refer to the method arguments are charged before the input is decoded,
+/// making sure that weight is paid even if the input is malformed. Weights that refer to the method
+/// arguments can only be charged after decoding the input, so such methods should additionally
+/// be covered by `per_input_byte` or [`base_weight`](#weight-requirements).
+///
 /// OBCE also provides you with a pre-charging feature, which charges weight before
-/// any data parsing is done, making sure that weight is paid even if the call
-/// is not successful:
+/// any data parsing is done and makes the charged amount available via `pre_charged`:
 ///
 /// ```ignore
 /// use obce::substrate::{
@@ -176,6 +485,9 @@ pub fn definition(attrs: TokenStream, trait_item: TokenStream) -> TokenStream {
 /// fn main() {}
 /// ```
 ///
+/// When combined with `actual`, the pre-charged amount is kept by OBCE to be adjusted
+/// after the call, and `pre_charged` returns [`None`].
+///
 /// ## Usage example
 ///
 /// ```ignore
@@ -254,6 +566,177 @@ pub fn definition(attrs: TokenStream, trait_item: TokenStream) -> TokenStream {
 /// ```
 ///
 /// This is done to ease chain extension environment generalization during testing.
+///
+/// Since `Ext` trait is sealed by `pallet-contracts`, you may want to use `obce::substrate::ContractExt`
+/// instead, which is implemented for every `Ext` implementation and provides access to the caller,
+/// contract address, balances, block number, timestamp, transfers, events and contract storage.
+/// Unlike `Ext`, `ContractExt` is also implemented by `obce::substrate::testing::MockExt`.
+/// To use it, replace `E: Ext<T = T>` bound with `E: ContractExt<T = T>`, importing `ContractExt`
+/// from `obce::substrate`.
+///
+/// # Input limits
+///
+/// Method inputs that implement `MaxEncodedLen` are automatically read with bounded length
+/// and decoding depth, returning a critical error if the input is longer than its maximum encoded length.
+///
+/// For the rest of the inputs, you can use `#[obce(max_input_len = ...)]` to reject inputs
+/// that are longer than the provided amount of bytes before reading them, and `#[obce(max_depth = ...)]`
+/// to limit the decoding depth:
+///
+/// ```ignore
+/// #[obce::implementation]
+/// impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+/// where
+///     T: SysConfig + ContractConfig,
+///     <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+///     Env: ChainExtensionEnvironment<E, T>,
+/// {
+///     #[obce(max_input_len = 1024, max_depth = 4)]
+///     fn extension_method(&mut self, val: Vec<Vec<u8>>) {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// Exceeding these limits results in `obce::substrate::INPUT_LENGTH_EXCEEDED` and
+/// `obce::substrate::INPUT_DEPTH_EXCEEDED` critical errors correspondingly.
+///
+/// # Weight requirements
+///
+/// Methods without `#[obce(weight(...))]` don't charge any weight for their execution.
+/// You can use `#[obce::implementation(require_weight)]` to make such methods a compile-time error.
+///
+/// Additionally, `#[obce::implementation(base_weight = "...")]` charges the provided amount of weight
+/// for every call to your chain extension, before any method logic runs:
+///
+/// ```ignore
+/// #[obce::implementation(require_weight, base_weight = "Weight::from_parts(1_000, 0)")]
+/// impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+/// where
+///     T: SysConfig + ContractConfig,
+///     <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+///     Env: ChainExtensionEnvironment<E, T>,
+/// {
+///     #[obce(weight(reads = 1))]
+///     fn extension_method(&mut self, val: u64) {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// # Unknown methods
+///
+/// Calls to methods that are unknown to your chain extension return `obce::codegen::UNKNOWN_FUNCTION_STATUS_CODE`
+/// status code, which `try_*` methods report as `CallError::UnknownFunction`. Methods generated by
+/// [`#[obce::definition]`](macro@definition) always check status codes, thus such calls can be handled gracefully.
+///
+/// Contracts, that use glue code which ignores status codes, decode an output buffer that was never written
+/// by the chain extension. If your chain extension is called by such contracts, use
+/// `#[obce::implementation(trap_unknown_functions)]` to return `InvalidFunctionId` critical error instead,
+/// which traps the caller contract.
+///
+/// `#[obce::implementation(unknown_function_status)]` is still accepted, but has no effect.
+///
+/// # Benchmarking
+///
+/// With `#[obce::implementation(benchmarks)]`, OBCE generates a `frame_benchmarking`
+/// benchmark for every chain extension method, which calls your chain extension through
+/// `CallableChainExtension::call` using `obce::substrate::benchmarking::BenchmarkEnvironment`.
+/// The generated benchmarks are available with `runtime-benchmarks` feature enabled in your crate, and are
+/// implemented on a generated `{Trait}Benchmarks<T>` type (for example, `ChainExtensionDefinitionBenchmarks<T>`),
+/// so they don't conflict with the benchmarks of your pallets. You can add this type to your runtime
+/// benchmarks, for example, with `[chain_extension, ChainExtensionDefinitionBenchmarks::<Runtime>]`
+/// entry of `define_benchmarks!`. Your chain extension struct should implement [`Default`].
+///
+/// Methods with arguments require `#[obce(benchmark(...))]` attribute, that accepts benchmark
+/// `components`, `setup` statements and `args` to call the method with:
+///
+/// ```ignore
+/// #[obce::implementation(benchmarks, weight_info = "T::ExtensionWeightInfo")]
+/// impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+/// where
+///     T: SysConfig + ContractConfig + crate::pallet::Config,
+///     <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+///     Env: ChainExtensionEnvironment<E, T>,
+/// {
+///     #[obce(
+///         weight(info = "items.len() as u32"),
+///         benchmark(
+///             components(n = "0 .. 1000"),
+///             setup = "let items: Vec<u32> = (0..n).collect();",
+///             args = "items"
+///         )
+///     )]
+///     fn sum(&mut self, items: Vec<u32>) -> u64 {
+///         items.into_iter().map(u64::from).sum()
+///     }
+/// }
+/// ```
+///
+/// Benchmarks use `()` as an `Ext` implementation by default. If your implementation requires `Ext`,
+/// you can provide its value with `benchmarks(ext = "...")`.
+///
+/// Alongside the benchmarks, OBCE generates a `{Trait}WeightInfo` trait (for example, `ChainExtensionDefinitionWeightInfo`),
+/// that contains a weight function for each method, accepting the benchmark components. You can implement
+/// this trait using the benchmarking results, provide the implementation via `weight_info = "..."`,
+/// and charge weight with `#[obce(weight(info))]`, or `#[obce(weight(info = "..."))]` with
+/// comma-separated component values.
+///
+/// # Testing
+///
+/// With the `std` feature enabled, you can use `obce::substrate::testing::MockEnvironment`
+/// to test your implementation without a runtime. The mock environment enforces weight limit,
+/// output buffer length and `SENTINEL` output skipping, while recording charged weights,
+/// weight adjustments and writes:
+///
+/// ```ignore
+/// use obce::substrate::testing::MockEnvironment;
+///
+/// let mut env = MockEnvironment::<_, Runtime>::new(())
+///     .with_weight_limit(Weight::from_parts(1_000_000, 0));
+///
+/// let output = env.call::<dyn ChainExtensionDefinition, { obce::method_hash!(ChainExtensionDefinition::sum) }, _>(
+///     &mut ChainExtension,
+///     vec![1, 2, 3],
+/// );
+///
+/// assert_eq!(output, Ok(6));
+/// assert_eq!(env.charged_weights().len(), 1);
+/// ```
+///
+/// Implementations that are bounded by `E: ContractExt<T = T>` can be tested using
+/// `obce::substrate::testing::MockExt`, which allows you to configure the caller, contract address,
+/// transferred value, balances, block number, timestamp and contract storage, and to inspect
+/// deposited events, transfers and storage writes after the call:
+///
+/// ```ignore
+/// use obce::substrate::testing::{MockEnvironment, MockExt};
+///
+/// let ext = MockExt::<Runtime>::new(ALICE, CONTRACT).with_balance(CONTRACT, 1000);
+/// let mut env = MockEnvironment::<_, Runtime>::new(ext);
+///
+/// env.call::<dyn ChainExtensionDefinition, { obce::method_hash!(ChainExtensionDefinition::refund_caller) }, _>(
+///     &mut ChainExtension,
+///     100,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(env.ext().transfers(), &[(CONTRACT, ALICE, 100)]);
+/// ```
+///
+/// With the `fuzz` feature enabled, `obce::substrate::testing::fuzz` module allows you to feed
+/// arbitrary inputs and method identifiers through your chain extension, checking that it doesn't panic
+/// and always produces outputs that fit the output buffer. It also checks that weight is charged before reading
+/// the input: the dispatcher charges method weight before reading method input, unless the weight depends on method
+/// arguments, in which case the method should be covered by `base_weight` or `per_input_byte` weight
+/// (or the check can be disabled via `FuzzConfig::require_charge_before_read`).
+/// You can generate a `cargo fuzz` target using `obce::fuzz_target!` macro:
+///
+/// ```ignore
+/// #![no_main]
+///
+/// obce::fuzz_target!(dyn ChainExtensionDefinition, ChainExtension, Runtime);
+/// ```
 #[proc_macro_attribute]
 pub fn implementation(attrs: TokenStream, impl_item: TokenStream) -> TokenStream {
     match implementation::generate(attrs.into(), impl_item.into()) {
@@ -305,7 +788,8 @@ pub fn implementation(attrs: TokenStream, impl_item: TokenStream) -> TokenStream
 /// [`TryFrom<YourError>`](::core::convert::TryFrom) for `pallet_contracts::chain_extension::RetVal`,
 /// which will automatically convert suitable error variants to `RetVal` on implementation methods marked with `#[obce(ret_val)]`.
 ///
-/// Error variant's `#[obce(ret_val = "...")]` accepts an expression that evaluates to [`u32`]:
+/// Error variant's `#[obce(ret_val = "...")]` accepts an expression that evaluates to [`u32`],
+/// except for `u32::MAX`, which is reserved for methods that are unknown to the chain extension:
 ///
 /// ```ignore
 /// #[obce::error]
@@ -316,6 +800,27 @@ pub fn implementation(attrs: TokenStream, impl_item: TokenStream) -> TokenStream
 ///     Second
 /// }
 /// ```
+///
+/// With `ink` feature enabled, [`#[obce::error]`](macro@error) also implements
+/// `obce::ink_lang::FromRetVal` and `FromStatusCode` for your error, mapping each `ret_val` value back into the
+/// corresponding variant. `FromRetVal` implementation is used by definition methods marked with `#[obce(ret_val)]`.
+///
+/// Status codes that don't correspond to any variant can be mapped into a variant marked with
+/// `#[obce(unknown_status)]`, which should contain the status code as a single unnamed `u32` field:
+///
+/// ```ignore
+/// #[obce::error]
+/// enum Error {
+///     #[obce(ret_val = "10_001")]
+///     First,
+///
+///     #[obce(unknown_status)]
+///     Unknown(u32),
+/// }
+/// ```
+///
+/// If there is no such variant, `try_*` methods return such status codes as `CallError::Status`,
+/// while `FromStatusCode` implementation panics.
 #[proc_macro_attribute]
 pub fn error(attrs: TokenStream, enum_item: TokenStream) -> TokenStream {
     match error::generate(attrs.into(), enum_item.into()) {
@@ -328,8 +833,8 @@ pub fn error(attrs: TokenStream, enum_item: TokenStream) -> TokenStream {
 ///
 /// # Description
 ///
-/// You can use [`#[obce::mock]`](macro@mock) to automatically generate `register_chain_extensions`
-/// function, which accepts a context and automatically registers mocked chain extension methods
+/// You can use [`#[obce::mock]`](macro@mock) to automatically generate a `{Trait}Registration` type,
+/// whose `register` function accepts a context and automatically registers mocked chain extension methods
 /// for off-chain ink! smart contract testing.
 ///
 /// Such a testing is useful to check smart contract's behaviour in the absence of
@@ -352,7 +857,7 @@ pub fn error(attrs: TokenStream, enum_item: TokenStream) -> TokenStream {
 ///
 /// #[test]
 /// fn call_contract() {
-///     register_chain_extensions(());
+///     MyChainExtensionRegistration::register(());
 ///     let mut contract = SimpleContract::new();
 ///     assert_eq!(contract.call_test_method(100, 200), 300);
 /// }
@@ -439,7 +944,7 @@ pub fn error(attrs: TokenStream, enum_item: TokenStream) -> TokenStream {
 ///
 ///     #[test]
 ///     fn call_contract() {
-///         register_chain_extensions(State::default());
+///         TraitRegistration::register(State::default());
 ///         let mut contract = crate::simple_contract::SimpleContract::new();
 ///         assert_eq!(contract.call_method(), 1);
 ///         assert_eq!(contract.call_method(), 2);
@@ -448,6 +953,166 @@ pub fn error(attrs: TokenStream, enum_item: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
+/// # Call recording
+///
+/// `register` returns an `obce::ink_lang::mock::MockHandle`, which provides
+/// access to the testing context after contract calls, as well as to every call made to the mocked
+/// methods, including the method name, method identifier and encoded arguments:
+///
+/// ```ignore
+/// #[test]
+/// fn call_contract() {
+///     let handle = TraitRegistration::register(State::default());
+///     let mut contract = crate::simple_contract::SimpleContract::new();
+///     contract.transfer(ALICE, 100);
+///     contract.transfer(BOB, 200);
+///
+///     assert_eq!(handle.context().call_count, 2);
+///
+///     let args: Vec<([u8; 32], u128)> = handle.calls_to("transfer").iter().map(|call| call.args()).collect();
+///     assert_eq!(args, vec![(ALICE, 100), (BOB, 200)]);
+/// }
+/// ```
+///
+/// Arguments are decoded in the same form as they are represented in `MethodDescription::Input`,
+/// that is, `A` for single-argument methods and `(A, B, ...)` for methods with multiple arguments.
+///
+/// # Multiple mocks
+///
+/// Registration types can also be used to install multiple mocked chain extensions at once
+/// with `obce::ink_lang::mock::MockRegistry`.
+/// The registry panics if mocks use conflicting function identifiers, and can be reset between phases of a test:
+///
+/// ```ignore
+/// #[obce::mock(registry_only)]
+/// impl crate::Balances for State {
+///     fn balance_of(&mut self, account: [u8; 32]) -> u128 {
+///         self.balance
+///     }
+/// }
+///
+/// #[obce::mock(registry_only)]
+/// impl crate::Assets for () {
+///     fn asset_balance_of(&mut self, asset: u32, account: [u8; 32]) -> u128 {
+///         0
+///     }
+/// }
+///
+/// #[test]
+/// fn call_contract() {
+///     let mut registry = MockRegistry::new()
+///         .with::<BalancesRegistration>(State { balance: 100 })
+///         .with::<AssetsRegistration>(())
+///         .install();
+///
+///     let handle = registry.handle::<BalancesRegistration>().unwrap();
+///
+///     // Test the contract with both chain extensions.
+///
+///     registry.reset();
+///
+///     let registry = registry.with::<BalancesRegistration>(State { balance: 0 }).install();
+///
+///     // Test the contract with the new mock.
+/// }
+/// ```
+///
+/// `registry_only` skips generation of the deprecated `register_chain_extensions` function
+/// (see [Legacy registration](#legacy-registration)), which allows you to place multiple mocks in the same module.
+///
+/// Mocks of the same trait in the same module require distinct registration type names,
+/// which can be specified via `#[obce::mock(registration = "...")]`:
+///
+/// ```ignore
+/// #[obce::mock(registration = "EmptyBalances")]
+/// impl crate::Balances for () {
+///     fn balance_of(&mut self, account: [u8; 32]) -> u128 {
+///         0
+///     }
+/// }
+///
+/// #[test]
+/// fn call_contract() {
+///     EmptyBalances::register(());
+///
+///     // Test the contract with the empty balances mock.
+/// }
+/// ```
+///
+/// # Legacy registration
+///
+/// Previous versions of OBCE generated a `register_chain_extensions` function instead of registration types.
+/// The function is still generated for mocks without `registration` and `registry_only` arguments,
+/// but is deprecated and forwards to `{Trait}Registration::register`:
+///
+/// ```ignore
+/// #[obce::mock]
+/// impl crate::Trait for () {
+///     fn method(&mut self, val: u32) -> u32 {
+///         val
+///     }
+/// }
+///
+/// #[test]
+/// #[allow(deprecated)]
+/// fn call_contract() {
+///     // Same as `TraitRegistration::register(())`.
+///     register_chain_extensions(());
+/// }
+/// ```
+///
+/// # Status codes and traps
+///
+/// Mocked methods may return `obce::ink_lang::MockResult` of the method output type
+/// instead of the output itself to simulate the runtime behaviour:
+///
+/// * `MockResult::Return(value)` returns the provided value, as if the method returned it directly.
+/// * `MockResult::Status(code)` returns the provided status code, which allows you to test
+///   `#[obce(ret_val)]` error variants and `CallError::Status` handling.
+/// * `MockResult::Trap(reason)` simulates a critical error, that traps the contract. Since ink!
+///   off-chain environment can't revert the contract execution, the test call panics instead.
+///
+/// ```ignore
+/// #[obce::mock]
+/// impl crate::Trait for () {
+///     fn transfer(&mut self, to: [u8; 32], value: u128) -> MockResult<Result<(), Error>> {
+///         MockResult::Status(100)
+///     }
+///
+///     fn balance_of(&mut self, account: [u8; 32]) -> MockResult<u128> {
+///         MockResult::Trap("storage is corrupted")
+///     }
+/// }
+/// ```
+///
+/// # Expectation mocks
+///
+/// In addition to [`#[obce::mock]`](macro@mock), every non-generic [`#[obce::definition]`](macro@definition)
+/// generates a `Mock{Trait}` struct in `std` and test builds. Each method gets an `expect_<method>()` builder,
+/// which can be refined with `.with(args)` (or `.withf(predicate)`), `.times(n)` and `.returning(closure)`.
+/// Method arguments are passed to matchers and closures as a tuple (or as a single value
+/// for single-argument methods), and closures may return a `MockResult` as well.
+///
+/// Expectations are matched in the order they were added, and call counts are verified when the mock is dropped.
+/// Methods that use associated types are not included in the generated mock, which is noted
+/// in the `Mock{Trait}` documentation. Generic definitions don't get an expectation mock at all.
+/// Use [`#[obce::mock]`](macro@mock) to mock such methods and definitions.
+///
+/// ```ignore
+/// #[test]
+/// fn call_contract() {
+///     let mock = MockChainExtension::new();
+///     mock.expect_method()
+///         .with((100, 200))
+///         .times(1)
+///         .returning(|(val, another_val)| val + another_val);
+///     mock.register();
+///
+///     let mut contract = SimpleContract::new();
+///     assert_eq!(contract.call_method(100, 200), 300);
+/// }
+/// ```
+///
 /// # General guidelines
 ///
 /// Since [`#[obce::mock]`](macro@mock) is designed for off-chain testing, you are
@@ -507,7 +1172,7 @@ pub fn error(attrs: TokenStream, enum_item: TokenStream) -> TokenStream {
 ///
 ///     #[test]
 ///     fn call_contract() {
-///         register_chain_extensions(());
+///         ChainExtensionRegistration::register(());
 ///         let mut contract = crate::simple_contract::SimpleContract::new();
 ///         assert_eq!(contract.call_method(100, 200), 300);
 ///     }
@@ -606,3 +1271,62 @@ pub fn id(path: TokenStream) -> TokenStream {
         Err(error) => error.to_compile_error().into(),
     }
 }
+
+/// Chain extension method hash lookup.
+///
+/// # Description
+///
+/// Using [`obce::method_hash!`](macro@method_hash) macro, you can lookup the `METHOD_HASH` generic
+/// of `MethodDescription` for the provided chain extension method.
+///
+/// # Example
+///
+/// ```ignore
+/// #[obce::definition]
+/// pub trait ChainExtension {
+///     fn method(&self, val: u32) -> u64;
+/// }
+///
+/// type Input = <dyn ChainExtension as obce::codegen::MethodDescription<{ obce::method_hash!(ChainExtension::method) }>>::Input;
+/// ```
+#[proc_macro]
+pub fn method_hash(path: TokenStream) -> TokenStream {
+    match id::generate_method_hash(path.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Compile-time uniqueness check for chain extension identifiers.
+///
+/// # Description
+///
+/// Chain extensions that are registered in the same runtime should have unique identifiers,
+/// otherwise calls to one of them are silently routed to another.
+///
+/// Using [`obce::assert_unique_ids!`](macro@assert_unique_ids) macro, you can check that
+/// the provided chain extensions have unique identifiers, and that each of them has unique method identifiers
+/// (including aliases). Compilation fails with an error message containing the names of colliding chain extensions.
+///
+/// # Example
+///
+/// ```ignore
+/// #[obce::definition]
+/// pub trait Assets {
+///     fn balance_of(&self, account: [u8; 32]) -> u128;
+/// }
+///
+/// #[obce::definition]
+/// pub trait Staking {
+///     fn stake(&mut self, amount: u128);
+/// }
+///
+/// obce::assert_unique_ids!(dyn Assets, dyn Staking);
+/// ```
+#[proc_macro]
+pub fn assert_unique_ids(extensions: TokenStream) -> TokenStream {
+    match unique_ids::generate(extensions.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
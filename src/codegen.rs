@@ -19,6 +19,27 @@
 // OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
+/// Status code that is returned to the caller contract when it calls a method that the chain extension
+/// doesn't support, unless the chain extension is implemented with `#[obce::implementation(trap_unknown_functions)]`.
+///
+/// This value is reserved and can't be used with `#[obce(ret_val = ...)]`.
+pub const UNKNOWN_FUNCTION_STATUS_CODE: u32 = u32::MAX;
+
+/// Method identifiers that are reserved for the capability handshake.
+///
+/// Chain extensions implemented with `#[obce::implementation]` answer these methods automatically,
+/// thus chain extension methods can't use identifiers from this range.
+pub const RESERVED_FUNCTION_IDS: core::ops::RangeInclusive<u16> = 0xFFF0..=0xFFFF;
+
+/// Reserved method identifier that returns [`ExtensionDescription::VERSION`] as `u16`.
+pub const VERSION_FUNCTION_ID: u16 = 0xFFFF;
+
+/// Reserved method identifier that returns [`ExtensionDescription::METHOD_IDS`] as `Vec<u16>`.
+pub const SUPPORTED_METHODS_FUNCTION_ID: u16 = 0xFFFE;
+
+/// Reserved method identifier that returns [`ExtensionDescription::ABI_HASH`] as `u32`.
+pub const ABI_HASH_FUNCTION_ID: u16 = 0xFFFD;
+
 /// Chain extension description.
 ///
 /// This trait is automatically implemented with `#[obce::definition]` macro expansion.
@@ -29,6 +50,77 @@
 pub trait ExtensionDescription {
     /// Unique chain extension identifier.
     const ID: u16;
+
+    /// Chain extension version, that can be changed via `#[obce::definition(version = ...)]` macro.
+    const VERSION: u16;
+
+    /// Scheme that was used to derive identifiers that weren't specified explicitly.
+    const ID_SCHEME: IdScheme;
+
+    /// Identifiers of all chain extension methods, including their aliases.
+    const METHOD_IDS: &'static [u16];
+
+    /// Whether method signature hashes are folded into method identifiers,
+    /// which can be enabled via `#[obce::definition(strict_abi)]` macro.
+    const STRICT_ABI: bool;
+
+    /// Chain extension ABI hash, that is computed from identifiers, alias identifiers and
+    /// [signature hashes](MethodDescription::SIGNATURE_HASH) of all chain extension methods.
+    const ABI_HASH: u32;
+}
+
+/// Scheme that is used to derive chain extension and method identifiers,
+/// that can be changed via `#[obce::definition(id_scheme = ...)]` macro.
+///
+/// Identifiers are derived by hashing the described string with BLAKE2b-256
+/// and taking the first two bytes of the hash as a big-endian [`u16`].
+/// Identifiers that are specified explicitly via `id = "..."` hash the provided string as-is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IdScheme {
+    /// Hash the bare trait name and the bare method name (for example, `method`).
+    Name,
+    /// Hash the `crate::Trait` path and the `crate::Trait::method` path,
+    /// where `crate` is the name of the crate that contains the definition.
+    Path(&'static str),
+    /// Hash the `namespace::Trait` path and the `namespace::method` path.
+    Namespace(&'static str),
+    /// All identifiers are specified explicitly.
+    Explicit,
+}
+
+/// Fail compilation if the provided method identifiers contain duplicates.
+///
+/// Used by [`obce::assert_unique_ids!`](macro@crate::assert_unique_ids) macro.
+#[doc(hidden)]
+pub const fn assert_unique_method_ids(ids: &[u16], message: &str) {
+    let mut i = 0;
+
+    while i < ids.len() {
+        let mut j = i + 1;
+
+        while j < ids.len() {
+            if ids[i] == ids[j] {
+                panic!("{}", message)
+            }
+
+            j += 1;
+        }
+
+        i += 1;
+    }
+}
+
+/// Maximum decoding depth, that is used by round-trip tests.
+///
+/// Mirrors the decoding depth limit of `pallet-contracts`.
+const ROUNDTRIP_DECODE_DEPTH: u32 = 256;
+
+/// Decode the whole provided buffer, limiting the decoding depth.
+///
+/// Used by round-trip tests, that are generated with `#[obce::definition(proptest)]`.
+#[doc(hidden)]
+pub fn decode_roundtrip<T: scale::Decode>(bytes: &[u8]) -> Result<T, scale::Error> {
+    <T as scale::DecodeLimit>::decode_all_with_depth_limit(ROUNDTRIP_DECODE_DEPTH, &mut &bytes[..])
 }
 
 /// Chain extension method description.
@@ -45,9 +137,56 @@ pub trait MethodDescription<const METHOD_HASH: u32> {
     /// Unique chain extension method identifier.
     const ID: u16;
 
+    /// Additional method identifiers, that are routed to the same method implementation.
+    ///
+    /// Aliases can be added via `#[obce(alias_id = ...)]` macro to keep serving
+    /// contracts that were built using previous method identifiers.
+    const ALIAS_IDS: &'static [u16];
+
+    /// Method signature hash, that is computed from the input and output types as they are spelled
+    /// in the chain extension definition.
+    ///
+    /// The hash is syntactic: macros can't resolve types, so it is computed over type tokens rather than
+    /// over resolved types (or their `scale-info` paths). Changes inside of user-defined types, such as adding
+    /// a field to a struct, don't change the hash.
+    ///
+    /// The types are hashed in the `(Input1,Input2)->Output` form, with all whitespace removed,
+    /// using BLAKE2b-256 and taking the first four bytes of the hash as a big-endian [`u32`].
+    /// Primitive and prelude type paths from `core`, `alloc` and `std` are reduced to their last segment,
+    /// so `u32`, `core::primitive::u32` and `::core::primitive::u32` produce the same hash. Other type paths
+    /// are hashed as written (without the leading `::`), so `a::Foo` and `b::Foo` produce different hashes.
+    /// Type aliases can't be resolved by macros, thus an alias hashes differently from its target type.
+    ///
+    /// With [`STRICT_ABI`](ExtensionDescription::STRICT_ABI) enabled, the lower
+    /// two bytes of this hash (and an optional `#[obce(salt = ...)]`) are XOR-ed into the
+    /// [`ID`](MethodDescription::ID), thus changing a method signature changes its identifier.
+    /// Since only 16 bits are used, different signatures may still produce the same identifier.
+    /// Collisions within a single chain extension are rejected at compile time, while collisions
+    /// with the identifiers of previous signatures can't be detected.
+    const SIGNATURE_HASH: u32;
+
+    /// Chain extension version in which the method was introduced,
+    /// that can be changed via `#[obce(since = ...)]` macro (`1` by default).
+    const SINCE: u16;
+
+    /// Whether method errors are converted into status codes,
+    /// which can be enabled via `#[obce(ret_val)]` macro.
+    ///
+    /// Chain extension implementations are checked against this value at compile time,
+    /// so that both sides of a chain extension agree on which errors are returned as status codes.
+    const RET_VAL: bool;
+
     /// Method input type, that is required for chain extension calls.
     type Input;
 
     /// Method output type, that you can use to obtain results from chain extension calls.
     type Output;
 }
+
+/// Placeholder for associated types of chain extension definitions.
+///
+/// Associated types are resolved separately by each side of a chain extension,
+/// thus [`MethodDescription`] input and output types use this placeholder in their place.
+///
+/// `NAME_HASH` generic is dependent solely on the associated type name.
+pub struct AssociatedType<const NAME_HASH: u32>;
@@ -0,0 +1,135 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use ink::{
+    env::chain_extension::{
+        ChainExtensionMethod,
+        FromStatusCode,
+        IsResultType,
+    },
+    prelude::{
+        vec,
+        vec::Vec,
+    },
+};
+
+use crate::codegen::UNKNOWN_FUNCTION_STATUS_CODE;
+
+/// Error that can occur when calling chain extension methods using
+/// their `try_*` counterparts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum CallError {
+    /// Chain extension output could not be decoded into the method output type.
+    Decode,
+
+    /// The runtime doesn't support the called method.
+    ///
+    /// Chain extensions implemented with `#[obce::implementation(trap_unknown_functions)]`
+    /// or with older OBCE versions trap the contract instead.
+    UnknownFunction,
+
+    /// The runtime returned a non-zero status code that can't be handled by the method.
+    Status(u32),
+}
+
+/// Conversion of non-zero status codes into error variants.
+///
+/// This trait is automatically implemented for errors marked with [`#[obce::error]`](macro@crate::error),
+/// mapping each `ret_val` value back into the corresponding variant.
+pub trait FromRetVal: Sized {
+    /// Convert a non-zero status code into an error.
+    ///
+    /// Returns [`None`] if the status code doesn't correspond to any error variant,
+    /// including the one marked with `#[obce(unknown_status)]`.
+    fn from_ret_val(status_code: u32) -> Option<Self>;
+}
+
+/// Chain extension output that consumes the whole output buffer without decoding it.
+struct RawOutput(Vec<u8>);
+
+impl scale::Decode for RawOutput {
+    fn decode<I: scale::Input>(input: &mut I) -> Result<Self, scale::Error> {
+        let mut output = vec![0; input.remaining_len()?.unwrap_or_default()];
+        input.read(&mut output)?;
+        Ok(RawOutput(output))
+    }
+}
+
+/// Raw non-zero status code.
+struct StatusCode(u32);
+
+impl FromStatusCode for StatusCode {
+    fn from_status_code(status_code: u32) -> Result<(), Self> {
+        match status_code {
+            0 => Ok(()),
+            _ => Err(StatusCode(status_code)),
+        }
+    }
+}
+
+fn call_raw<I: scale::Encode>(func_id: u32, input: &I) -> Result<Vec<u8>, CallError> {
+    ChainExtensionMethod::build(func_id)
+        .input::<I>()
+        .output::<RawOutput, false>()
+        .handle_error_code::<StatusCode>()
+        .call(input)
+        .map(|RawOutput(output)| output)
+        .map_err(|StatusCode(status_code)| {
+            if status_code == UNKNOWN_FUNCTION_STATUS_CODE {
+                CallError::UnknownFunction
+            } else {
+                CallError::Status(status_code)
+            }
+        })
+}
+
+#[doc(hidden)]
+pub fn try_call<I, O>(func_id: u32, input: &I) -> Result<O, CallError>
+where
+    I: scale::Encode,
+    O: scale::Decode,
+{
+    let output = call_raw(func_id, input)?;
+    O::decode(&mut &output[..]).map_err(|_| CallError::Decode)
+}
+
+#[doc(hidden)]
+pub fn try_call_with_ret_val<I, O>(func_id: u32, input: &I) -> Result<Result<O::Ok, O::Err>, CallError>
+where
+    I: scale::Encode,
+    O: IsResultType,
+    O::Ok: scale::Decode,
+    O::Err: scale::Decode + FromRetVal,
+{
+    match call_raw(func_id, input) {
+        Ok(output) => <Result<O::Ok, O::Err> as scale::Decode>::decode(&mut &output[..]).map_err(|_| CallError::Decode),
+        // Status codes that don't correspond to any `ret_val` or `unknown_status` variant
+        // are returned as is.
+        Err(CallError::Status(status_code)) => {
+            match <O::Err as FromRetVal>::from_ret_val(status_code) {
+                Some(error) => Ok(Err(error)),
+                None => Err(CallError::Status(status_code)),
+            }
+        }
+        Err(error) => Err(error),
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use ink::prelude::vec::Vec;
+
+use crate::{
+    codegen::{
+        ExtensionDescription,
+        ABI_HASH_FUNCTION_ID,
+        SUPPORTED_METHODS_FUNCTION_ID,
+        VERSION_FUNCTION_ID,
+    },
+    ink_lang::{
+        try_call,
+        CallError,
+    },
+};
+
+fn call_reserved<T, O>(func_id: u16) -> Result<O, CallError>
+where
+    T: ExtensionDescription + ?Sized,
+    O: scale::Decode,
+{
+    try_call::<(), O>((T::ID as u32) << 16 | (func_id as u32), &())
+}
+
+/// Get the version of the chain extension that is supported by the runtime.
+///
+/// ```ignore
+/// let version = obce::ink_lang::extension_version::<dyn ChainExtension>()?;
+/// ```
+pub fn extension_version<T: ExtensionDescription + ?Sized>() -> Result<u16, CallError> {
+    call_reserved::<T, _>(VERSION_FUNCTION_ID)
+}
+
+/// Get the identifiers of chain extension methods (including aliases) that are supported by the runtime.
+pub fn supported_methods<T: ExtensionDescription + ?Sized>() -> Result<Vec<u16>, CallError> {
+    call_reserved::<T, _>(SUPPORTED_METHODS_FUNCTION_ID)
+}
+
+/// Check whether the runtime supports the chain extension method with the provided identifier.
+///
+/// ```ignore
+/// if obce::ink_lang::is_method_supported::<dyn ChainExtension>(obce::id!(ChainExtension::method))? {
+///     ChainExtension.method();
+/// }
+/// ```
+pub fn is_method_supported<T: ExtensionDescription + ?Sized>(method_id: u16) -> Result<bool, CallError> {
+    supported_methods::<T>().map(|method_ids| method_ids.contains(&method_id))
+}
+
+/// Get the [ABI hash](ExtensionDescription::ABI_HASH) of the chain extension that is supported by the runtime.
+///
+/// If it matches the ABI hash the contract was built with, then all chain extension methods
+/// have the same identifiers and signatures on both sides.
+pub fn abi_hash<T: ExtensionDescription + ?Sized>() -> Result<u32, CallError> {
+    call_reserved::<T, _>(ABI_HASH_FUNCTION_ID)
+}
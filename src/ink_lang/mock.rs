@@ -0,0 +1,660 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::{
+    any::{
+        type_name,
+        Any,
+        TypeId,
+    },
+    cell::{
+        Ref,
+        RefCell,
+        RefMut,
+    },
+    collections::BTreeMap,
+    rc::Rc,
+};
+
+use ink::env::test::ChainExtension;
+
+use crate::codegen::{
+    ExtensionDescription,
+    ABI_HASH_FUNCTION_ID,
+    RESERVED_FUNCTION_IDS,
+    SUPPORTED_METHODS_FUNCTION_ID,
+    VERSION_FUNCTION_ID,
+};
+
+/// Result of a mocked chain extension method.
+///
+/// Mocked methods may return [`MockResult`] of the method output type instead of the output
+/// itself to simulate non-zero status codes and critical errors returned by the runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockResult<T> {
+    /// Return the provided output with zero status code.
+    Return(T),
+
+    /// Return the provided status code without any output, as it is done by `RetVal`-convertible
+    /// errors on the Substrate side of a chain extension.
+    Status(u32),
+
+    /// Trap the contract with the provided reason, as it is done by critical errors
+    /// on the Substrate side of a chain extension.
+    ///
+    /// Since ink! off-chain environment has no way to revert the contract execution,
+    /// trapping is emulated by panicking.
+    Trap(&'static str),
+}
+
+/// Conversion of mocked method outputs into [`MockResult`].
+#[doc(hidden)]
+pub trait IntoMockResult<T> {
+    fn into_mock_result(self) -> MockResult<T>;
+}
+
+impl<T> IntoMockResult<T> for T {
+    fn into_mock_result(self) -> MockResult<T> {
+        MockResult::Return(self)
+    }
+}
+
+impl<T> IntoMockResult<T> for MockResult<T> {
+    fn into_mock_result(self) -> MockResult<T> {
+        self
+    }
+}
+
+/// Chain extension call, that was recorded by a chain extension mock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCall {
+    /// Name of the called method.
+    pub method: &'static str,
+
+    /// Identifier of the called method.
+    pub func_id: u16,
+
+    /// Encoded method input.
+    pub input: Vec<u8>,
+}
+
+impl MockCall {
+    /// Decode the method arguments.
+    ///
+    /// Arguments are decoded in the same form as they are represented in [`MethodDescription::Input`](crate::codegen::MethodDescription::Input),
+    /// that is, `()` for methods without arguments, `A` for single-argument methods and `(A, B, ...)` for the rest.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the input can't be decoded as the provided type.
+    pub fn args<I: scale::Decode>(&self) -> I {
+        scale::Decode::decode(&mut &self.input[..]).expect("unable to decode mocked method arguments")
+    }
+}
+
+/// Handle to the chain extension mock, that is returned by `{Trait}Registration::register`
+/// function generated with [`#[obce::mock]`](macro@crate::mock) or by [`MockRegistry::handle`].
+///
+/// The handle provides access to the mock context and to the calls made by contracts.
+pub struct MockHandle<C> {
+    context: Rc<RefCell<C>>,
+    calls: Rc<RefCell<Vec<MockCall>>>,
+}
+
+impl<C> Clone for MockHandle<C> {
+    fn clone(&self) -> Self {
+        MockHandle {
+            context: self.context.clone(),
+            calls: self.calls.clone(),
+        }
+    }
+}
+
+impl<C> MockHandle<C> {
+    #[doc(hidden)]
+    pub fn new(context: C) -> Self {
+        MockHandle {
+            context: Rc::new(RefCell::new(context)),
+            calls: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Get the mock context.
+    pub fn context(&self) -> Ref<'_, C> {
+        self.context.borrow()
+    }
+
+    /// Get the mutable mock context.
+    pub fn context_mut(&self) -> RefMut<'_, C> {
+        self.context.borrow_mut()
+    }
+
+    /// Get all calls made to the mocked methods, in the order of calling.
+    pub fn calls(&self) -> Ref<'_, [MockCall]> {
+        Ref::map(self.calls.borrow(), Vec::as_slice)
+    }
+
+    /// Get all calls made to the mocked method with the provided name.
+    pub fn calls_to(&self, method: &str) -> Vec<MockCall> {
+        self.calls
+            .borrow()
+            .iter()
+            .filter(|call| call.method == method)
+            .cloned()
+            .collect()
+    }
+
+    #[doc(hidden)]
+    pub fn record(&self, call: MockCall) {
+        self.calls.borrow_mut().push(call);
+    }
+}
+
+impl<T: scale::Encode> MockResult<T> {
+    /// Write the output into the provided buffer, returning the status code.
+    ///
+    /// Panics if the result is a [`MockResult::Trap`].
+    #[doc(hidden)]
+    pub fn into_status_code(self, output: &mut Vec<u8>) -> u32 {
+        match self {
+            MockResult::Return(value) => {
+                scale::Encode::encode_to(&value, output);
+                0
+            }
+            MockResult::Status(status_code) => status_code,
+            MockResult::Trap(reason) => panic!("chain extension call trapped: {reason}"),
+        }
+    }
+}
+
+type Matcher<I> = Box<dyn Fn(&I) -> bool>;
+type Returning<I, O> = Box<dyn FnMut(I) -> MockResult<O>>;
+
+struct ExpectationState<I, O> {
+    matcher: Option<Matcher<I>>,
+    times: Option<usize>,
+    returning: Option<Returning<I, O>>,
+    calls: usize,
+}
+
+impl<I, O> ExpectationState<I, O> {
+    fn matches(&self, input: &I) -> bool {
+        match &self.matcher {
+            Some(matcher) => matcher(input),
+            None => true,
+        }
+    }
+
+    fn is_saturated(&self) -> bool {
+        self.times.is_some_and(|times| self.calls >= times)
+    }
+}
+
+/// Expectation of a mocked chain extension method call.
+///
+/// Expectations are created by `expect_<method>` methods of mocks, that are generated
+/// with [`#[obce::definition]`](macro@crate::definition).
+///
+/// Arguments are represented in the same form as in [`MethodDescription::Input`](crate::codegen::MethodDescription::Input),
+/// that is, `()` for methods without arguments, `A` for single-argument methods and `(A, B, ...)` for the rest.
+pub struct Expectation<I, O>(Rc<RefCell<ExpectationState<I, O>>>);
+
+impl<I: 'static, O: 'static> Expectation<I, O> {
+    /// Match only the calls with the provided arguments.
+    pub fn with(self, args: I) -> Self
+    where
+        I: PartialEq,
+    {
+        self.withf(move |input| input == &args)
+    }
+
+    /// Match only the calls, for which the provided predicate returns `true`.
+    pub fn withf<F>(self, matcher: F) -> Self
+    where
+        F: Fn(&I) -> bool + 'static,
+    {
+        self.0.borrow_mut().matcher = Some(Box::new(matcher));
+        self
+    }
+
+    /// Expect the method to be called exactly `times` times.
+    ///
+    /// The number of calls is verified when the mock is dropped.
+    pub fn times(self, times: usize) -> Self {
+        self.0.borrow_mut().times = Some(times);
+        self
+    }
+
+    /// Expect the method to never be called.
+    pub fn never(self) -> Self {
+        self.times(0)
+    }
+
+    /// Use the provided closure to produce the method output.
+    ///
+    /// The closure may return either the method output itself or [`MockResult`] of it.
+    pub fn returning<R, F>(self, mut returning: F) -> Self
+    where
+        R: IntoMockResult<O>,
+        F: FnMut(I) -> R + 'static,
+    {
+        self.0.borrow_mut().returning = Some(Box::new(move |input| returning(input).into_mock_result()));
+        self
+    }
+}
+
+/// Expectations of a single mocked chain extension method.
+#[doc(hidden)]
+pub struct Expectations<I, O> {
+    method: &'static str,
+    expectations: Rc<RefCell<Vec<Expectation<I, O>>>>,
+}
+
+impl<I, O> Clone for Expectations<I, O> {
+    fn clone(&self) -> Self {
+        Expectations {
+            method: self.method,
+            expectations: self.expectations.clone(),
+        }
+    }
+}
+
+impl<I: 'static, O: 'static> Expectations<I, O> {
+    pub fn new(method: &'static str) -> Self {
+        Expectations {
+            method,
+            expectations: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    pub fn expect(&self) -> Expectation<I, O> {
+        let state = Rc::new(RefCell::new(ExpectationState {
+            matcher: None,
+            times: None,
+            returning: None,
+            calls: 0,
+        }));
+
+        self.expectations.borrow_mut().push(Expectation(state.clone()));
+
+        Expectation(state)
+    }
+
+    /// Call the first matching expectation, that is not saturated yet.
+    pub fn call(&self, input: I) -> MockResult<O> {
+        let expectations = self.expectations.borrow();
+
+        let mut matching = expectations
+            .iter()
+            .filter(|expectation| expectation.0.borrow().matches(&input))
+            .peekable();
+
+        if matching.peek().is_none() {
+            panic!("unexpected call to `{}`: no matching expectation found", self.method)
+        }
+
+        let Some(expectation) = matching.find(|expectation| !expectation.0.borrow().is_saturated()) else {
+            panic!("`{}` was called more times than expected", self.method)
+        };
+
+        let mut state = expectation.0.borrow_mut();
+        state.calls += 1;
+
+        match &mut state.returning {
+            Some(returning) => returning(input),
+            None => {
+                panic!(
+                    "`{}` expectation has no return value, consider adding one with `returning`",
+                    self.method
+                )
+            }
+        }
+    }
+
+    /// Verify that every expectation was called the expected number of times.
+    ///
+    /// Verification is skipped if the current thread is already panicking.
+    pub fn verify(&self) {
+        if std::thread::panicking() {
+            return
+        }
+
+        for expectation in self.expectations.borrow().iter() {
+            let state = expectation.0.borrow();
+
+            if let Some(times) = state.times {
+                assert_eq!(
+                    state.calls, times,
+                    "`{}` was expected to be called {} times, but was called {} times",
+                    self.method, times, state.calls
+                );
+            }
+        }
+    }
+
+    /// Register the method expectations as a chain extension with the provided function identifier.
+    pub fn register(&self, func_id: u32)
+    where
+        I: scale::Decode,
+        O: scale::Encode,
+    {
+        ink::env::test::register_chain_extension(ExpectationsProxy {
+            func_id,
+            expectations: self.clone(),
+        });
+    }
+}
+
+struct ExpectationsProxy<I, O> {
+    func_id: u32,
+    expectations: Expectations<I, O>,
+}
+
+impl<I, O> ChainExtension for ExpectationsProxy<I, O>
+where
+    I: scale::Decode + 'static,
+    O: scale::Encode + 'static,
+{
+    fn func_id(&self) -> u32 {
+        self.func_id
+    }
+
+    fn call(&mut self, input: &[u8], output: &mut Vec<u8>) -> u32 {
+        let bytes: Vec<u8> = scale::Decode::decode(&mut &input[..]).unwrap();
+        let input: I = scale::Decode::decode(&mut &bytes[..]).unwrap();
+
+        self.expectations.call(input).into_status_code(output)
+    }
+}
+
+/// Register the capability handshake of the chain extension described by `D`,
+/// that reports only the provided method identifiers as supported.
+#[doc(hidden)]
+pub fn register_capabilities<D>(method_ids: &[u16])
+where
+    D: ExtensionDescription + ?Sized,
+{
+    for extension in capabilities::<D>(method_ids) {
+        ink::env::test::register_chain_extension(Boxed(extension));
+    }
+}
+
+/// Create the capability handshake of the chain extension described by `D`,
+/// that reports only the provided method identifiers as supported.
+#[doc(hidden)]
+pub fn capabilities<D>(method_ids: &[u16]) -> Vec<Box<dyn ChainExtension>>
+where
+    D: ExtensionDescription + ?Sized,
+{
+    struct CapabilityProxy {
+        func_id: u32,
+        output: Vec<u8>,
+    }
+
+    impl ChainExtension for CapabilityProxy {
+        fn func_id(&self) -> u32 {
+            self.func_id
+        }
+
+        fn call(&mut self, _: &[u8], output: &mut Vec<u8>) -> u32 {
+            output.extend_from_slice(&self.output);
+            0
+        }
+    }
+
+    let trait_id = (D::ID as u32) << 16;
+
+    vec![
+        Box::new(CapabilityProxy {
+            func_id: trait_id | VERSION_FUNCTION_ID as u32,
+            output: scale::Encode::encode(&D::VERSION),
+        }),
+        Box::new(CapabilityProxy {
+            func_id: trait_id | SUPPORTED_METHODS_FUNCTION_ID as u32,
+            output: scale::Encode::encode(&method_ids),
+        }),
+        Box::new(CapabilityProxy {
+            func_id: trait_id | ABI_HASH_FUNCTION_ID as u32,
+            output: scale::Encode::encode(&D::ABI_HASH),
+        }),
+    ]
+}
+
+/// Registration of a chain extension mock.
+///
+/// [`#[obce::mock]`](macro@crate::mock) implements this trait for a `{Trait}Registration` type,
+/// that is generated alongside the mock and is used to install it with [`MockRegistry`].
+pub trait MockExtension {
+    /// Mocked chain extension (for example, `dyn Trait`).
+    type Extension: ExtensionDescription + ?Sized;
+
+    /// Mock context.
+    type Context;
+
+    /// Create chain extension proxies, that forward calls of mocked methods to the mock with the provided handle.
+    ///
+    /// Capability handshake isn't a part of the proxies, since it's shared by all mocks of the same chain extension.
+    #[doc(hidden)]
+    fn extensions(handle: &MockHandle<Self::Context>) -> Vec<Box<dyn ChainExtension>>;
+}
+
+/// Register the mock with the provided context in the ink! off-chain environment,
+/// as it is done by `{Trait}Registration::register` function.
+#[doc(hidden)]
+pub fn register<M: MockExtension>(context: M::Context) -> MockHandle<M::Context> {
+    let handle = MockHandle::new(context);
+    let extensions = M::extensions(&handle);
+    let method_ids: Vec<_> = extensions.iter().map(|extension| extension.func_id() as u16).collect();
+
+    // Capability handshake, that reports only mocked methods as supported.
+    for extension in capabilities::<M::Extension>(&method_ids).into_iter().chain(extensions) {
+        ink::env::test::register_chain_extension(Boxed(extension));
+    }
+
+    handle
+}
+
+struct Boxed(Box<dyn ChainExtension>);
+
+impl ChainExtension for Boxed {
+    fn func_id(&self) -> u32 {
+        self.0.func_id()
+    }
+
+    fn call(&mut self, input: &[u8], output: &mut Vec<u8>) -> u32 {
+        self.0.call(input, output)
+    }
+}
+
+struct Registration {
+    extension: &'static str,
+    proxy: Box<dyn ChainExtension>,
+}
+
+type Registrations = Rc<RefCell<BTreeMap<u32, Registration>>>;
+
+/// Registry of chain extension mocks.
+///
+/// Unlike `{Trait}Registration::register` function generated with [`#[obce::mock]`](macro@crate::mock),
+/// the registry allows you to use multiple mocked chain extensions at once,
+/// detects conflicting function identifiers and can be reset between phases of a test.
+///
+/// ```ignore
+/// let mut registry = MockRegistry::new()
+///     .with::<BalancesRegistration>(BalancesContext::default())
+///     .with::<AssetsRegistration>(AssetsContext::default())
+///     .install();
+///
+/// // Test the contract with both chain extensions.
+///
+/// registry.reset();
+///
+/// // Calls to previously registered chain extensions panic from now on.
+/// ```
+#[derive(Default)]
+pub struct MockRegistry {
+    registrations: Registrations,
+    handles: Vec<(TypeId, Box<dyn Any>)>,
+}
+
+impl MockRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a mock with the provided context.
+    ///
+    /// Multiple mocks of the same chain extension can be added, as long as they mock different methods.
+    /// In that case the capability handshake reports methods of all such mocks as supported.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any function identifier of the mock is already used
+    /// by another mock in this registry.
+    pub fn with<M>(mut self, context: M::Context) -> Self
+    where
+        M: MockExtension + 'static,
+        M::Context: 'static,
+    {
+        let handle = MockHandle::new(context);
+
+        {
+            let mut registrations = self.registrations.borrow_mut();
+
+            for proxy in M::extensions(&handle) {
+                let func_id = proxy.func_id();
+
+                if let Some(registration) = registrations.get(&func_id) {
+                    panic!(
+                        "conflicting chain extension function identifier {func_id:#010x}, \
+                         that is used by both `{}` and `{}`",
+                        registration.extension,
+                        type_name::<M::Extension>()
+                    )
+                }
+
+                registrations.insert(
+                    func_id,
+                    Registration {
+                        extension: type_name::<M::Extension>(),
+                        proxy,
+                    },
+                );
+            }
+
+            // Capability handshake is registered once per chain extension, so it is replaced
+            // with the one that reports methods of every mock of the chain extension as supported.
+            let trait_id = M::Extension::ID as u32;
+            let method_ids: Vec<_> = registrations
+                .keys()
+                .filter(|&&func_id| func_id >> 16 == trait_id && !RESERVED_FUNCTION_IDS.contains(&(func_id as u16)))
+                .map(|&func_id| func_id as u16)
+                .collect();
+
+            for proxy in capabilities::<M::Extension>(&method_ids) {
+                let func_id = proxy.func_id();
+
+                if let Some(registration) = registrations
+                    .get(&func_id)
+                    .filter(|registration| registration.extension != type_name::<M::Extension>())
+                {
+                    panic!(
+                        "conflicting chain extension function identifier {func_id:#010x}, \
+                         that is used by both `{}` and `{}`",
+                        registration.extension,
+                        type_name::<M::Extension>()
+                    )
+                }
+
+                registrations.insert(
+                    func_id,
+                    Registration {
+                        extension: type_name::<M::Extension>(),
+                        proxy,
+                    },
+                );
+            }
+        }
+
+        self.handles.push((TypeId::of::<M>(), Box::new(handle)));
+        self
+    }
+
+    /// Register every added mock in the ink! off-chain environment.
+    pub fn install(self) -> Self {
+        for &func_id in self.registrations.borrow().keys() {
+            ink::env::test::register_chain_extension(RegistryProxy {
+                func_id,
+                registrations: self.registrations.clone(),
+            });
+        }
+
+        self
+    }
+
+    /// Get the handle of the provided mock.
+    ///
+    /// Returns `None` if the mock wasn't added to the registry.
+    pub fn handle<M>(&self) -> Option<MockHandle<M::Context>>
+    where
+        M: MockExtension + 'static,
+        M::Context: 'static,
+    {
+        self.handles
+            .iter()
+            .find(|(type_id, _)| *type_id == TypeId::of::<M>())
+            .and_then(|(_, handle)| handle.downcast_ref::<MockHandle<M::Context>>())
+            .cloned()
+    }
+
+    /// Remove every mock from the registry.
+    ///
+    /// Installed mocks are unregistered as well, so any subsequent call to them panics
+    /// until new mocks with the same function identifiers are installed.
+    pub fn reset(&mut self) {
+        self.registrations.borrow_mut().clear();
+        self.handles.clear();
+    }
+}
+
+struct RegistryProxy {
+    func_id: u32,
+    registrations: Registrations,
+}
+
+impl ChainExtension for RegistryProxy {
+    fn func_id(&self) -> u32 {
+        self.func_id
+    }
+
+    fn call(&mut self, input: &[u8], output: &mut Vec<u8>) -> u32 {
+        match self.registrations.borrow_mut().get_mut(&self.func_id) {
+            Some(registration) => registration.proxy.call(input, output),
+            None => {
+                panic!(
+                    "chain extension function {:#010x} was unregistered by `MockRegistry::reset`",
+                    self.func_id
+                )
+            }
+        }
+    }
+}
@@ -19,8 +19,27 @@
 // OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
+mod call;
+mod capabilities;
+#[cfg(feature = "std")]
+pub mod mock;
+
+pub use call::{
+    try_call,
+    try_call_with_ret_val,
+    CallError,
+    FromRetVal,
+};
+pub use capabilities::{
+    abi_hash,
+    extension_version,
+    is_method_supported,
+    supported_methods,
+};
 #[cfg(feature = "std")]
 pub use ink::metadata;
+#[cfg(feature = "std")]
+pub use mock::MockResult;
 pub use ink::{
     env,
     prelude,
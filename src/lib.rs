@@ -125,7 +125,7 @@
 //!
 //!     #[test]
 //!     fn call_contract() {
-//!         register_chain_extensions(Context);
+//!         ChainExtensionRegistration::register(Context);
 //!         // Call the contract as usual
 //!     }
 //! }
@@ -149,9 +149,11 @@ pub mod substrate;
 pub mod codegen;
 
 pub use obce_macro::{
+    assert_unique_ids,
     definition,
     error,
     id,
     implementation,
+    method_hash,
     mock,
 };
@@ -0,0 +1,133 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+//! Benchmarking utilities, that are used by [`#[obce::implementation]`](macro@crate::implementation)
+//! to benchmark chain extension methods.
+
+use core::marker::PhantomData;
+
+use frame_support::dispatch::Weight;
+use pallet_contracts::chain_extension::Result;
+use sp_core::{
+    Decode,
+    MaxEncodedLen,
+};
+use sp_std::vec::Vec;
+
+use crate::substrate::{
+    memory::MemoryInput,
+    ChainExtensionEnvironment,
+};
+
+/// In-memory [`ChainExtensionEnvironment`], that is used to drive chain extension calls in benchmarks.
+///
+/// Input is read and decoded the same way as in `MockEnvironment`, while weight charges
+/// always succeed, since the goal of a benchmark is to measure the execution time
+/// of a chain extension method itself.
+pub struct BenchmarkEnvironment<E, T> {
+    func_id: u16,
+    ext_id: u16,
+    input: MemoryInput,
+    output: Vec<u8>,
+    charged: Weight,
+    ext: E,
+    _ghost: PhantomData<T>,
+}
+
+impl<E, T> BenchmarkEnvironment<E, T> {
+    /// Create a new benchmarking environment with the provided identifiers, encoded input and `Ext` value.
+    pub fn new(ext_id: u16, func_id: u16, input: Vec<u8>, ext: E) -> Self {
+        BenchmarkEnvironment {
+            func_id,
+            ext_id,
+            input: MemoryInput::new(input),
+            output: Vec::new(),
+            charged: Weight::zero(),
+            ext,
+            _ghost: PhantomData,
+        }
+    }
+
+    /// Get the total amount of charged weight.
+    pub fn charged_weight(&self) -> Weight {
+        self.charged
+    }
+
+    /// Get the output written by the chain extension.
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+}
+
+impl<E, T> ChainExtensionEnvironment<E, T> for BenchmarkEnvironment<E, T> {
+    type ChargedAmount = Weight;
+
+    fn func_id(&self) -> u16 {
+        self.func_id
+    }
+
+    fn ext_id(&self) -> u16 {
+        self.ext_id
+    }
+
+    fn in_len(&self) -> u32 {
+        self.input.len()
+    }
+
+    fn read(&self, max_len: u32) -> Result<Vec<u8>> {
+        Ok(self.input.read(max_len).to_vec())
+    }
+
+    fn read_into(&self, buffer: &mut &mut [u8]) -> Result<()> {
+        self.input.read_into(buffer);
+        Ok(())
+    }
+
+    fn read_as<U: Decode + MaxEncodedLen>(&mut self) -> Result<U> {
+        self.input.read_as()
+    }
+
+    fn read_as_unbounded<U: Decode>(&mut self, len: u32) -> Result<U> {
+        self.input.read_as_unbounded(len)
+    }
+
+    fn write(&mut self, buffer: &[u8], _allow_skip: bool, _weight_per_byte: Option<Weight>) -> Result<()> {
+        self.output = buffer.to_vec();
+        Ok(())
+    }
+
+    fn charge_weight(&mut self, amount: Weight) -> Result<Self::ChargedAmount> {
+        self.charged = self.charged.saturating_add(amount);
+        Ok(amount)
+    }
+
+    fn adjust_weight(&mut self, charged: Self::ChargedAmount, actual_weight: Weight) {
+        self.charged = self.charged.saturating_sub(charged.saturating_sub(actual_weight));
+    }
+
+    fn remaining_weight(&mut self) -> Weight {
+        Weight::MAX
+    }
+
+    fn ext(&mut self) -> &mut E {
+        &mut self.ext
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use frame_support::traits::{
+    Currency,
+    Get,
+    Time,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_contracts::{
+    chain_extension::Ext,
+    exec::Key,
+    Config,
+    Error,
+    Origin,
+};
+use sp_runtime::{
+    DispatchError,
+    DispatchResult,
+};
+use sp_std::vec::Vec;
+
+type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+type BalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::Balance;
+type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
+type TopicOf<T> = <T as frame_system::Config>::Hash;
+
+/// Contract execution context, that is accessible from chain extensions.
+///
+/// `pallet_contracts::chain_extension::Ext` is sealed, thus it can't be implemented
+/// outside of `pallet-contracts`. [`ContractExt`] mirrors the commonly used subset
+/// of `Ext` methods, and is implemented for every `Ext` implementation, allowing you
+/// to use `E: ContractExt<T = T>` bound instead of `E: Ext<T = T>` to be able to
+/// test your chain extension using [`MockExt`](crate::substrate::testing::MockExt).
+pub trait ContractExt {
+    /// Runtime configuration.
+    type T: Config;
+
+    /// Get the caller of the current contract.
+    fn caller(&self) -> Origin<Self::T>;
+
+    /// Get the address of the current contract.
+    fn address(&self) -> &AccountIdOf<Self::T>;
+
+    /// Get the balance of the current contract.
+    fn balance(&self) -> BalanceOf<Self::T>;
+
+    /// Get the value transferred to the current contract.
+    fn value_transferred(&self) -> BalanceOf<Self::T>;
+
+    /// Get the current timestamp.
+    fn now(&self) -> &MomentOf<Self::T>;
+
+    /// Get the minimum balance that an account can hold.
+    fn minimum_balance(&self) -> BalanceOf<Self::T>;
+
+    /// Get the current block number.
+    fn block_number(&self) -> BlockNumberFor<Self::T>;
+
+    /// Transfer the provided value from the current contract to the `to` account.
+    fn transfer(&mut self, to: &AccountIdOf<Self::T>, value: BalanceOf<Self::T>) -> DispatchResult;
+
+    /// Deposit a contract event with the provided topics.
+    fn deposit_event(&mut self, topics: Vec<TopicOf<Self::T>>, data: Vec<u8>);
+
+    /// Get the storage entry of the current contract by the provided variable-sized key.
+    ///
+    /// Keys are hashed the same way ink! contracts hash their storage keys, thus
+    /// the chain extension and the contract can access the same storage entries.
+    fn get_storage(&mut self, key: &[u8]) -> Option<Vec<u8>>;
+
+    /// Set the storage entry of the current contract by the provided variable-sized key.
+    ///
+    /// Passing [`None`] as a value removes the storage entry. Keys, that are longer than
+    /// `MaxStorageKeyLen`, are rejected.
+    fn set_storage(&mut self, key: &[u8], value: Option<Vec<u8>>) -> DispatchResult;
+}
+
+impl<E: Ext> ContractExt for E {
+    type T = <E as Ext>::T;
+
+    fn caller(&self) -> Origin<Self::T> {
+        Ext::caller(self)
+    }
+
+    fn address(&self) -> &AccountIdOf<Self::T> {
+        Ext::address(self)
+    }
+
+    fn balance(&self) -> BalanceOf<Self::T> {
+        Ext::balance(self)
+    }
+
+    fn value_transferred(&self) -> BalanceOf<Self::T> {
+        Ext::value_transferred(self)
+    }
+
+    fn now(&self) -> &MomentOf<Self::T> {
+        Ext::now(self)
+    }
+
+    fn minimum_balance(&self) -> BalanceOf<Self::T> {
+        Ext::minimum_balance(self)
+    }
+
+    fn block_number(&self) -> BlockNumberFor<Self::T> {
+        Ext::block_number(self)
+    }
+
+    fn transfer(&mut self, to: &AccountIdOf<Self::T>, value: BalanceOf<Self::T>) -> DispatchResult {
+        Ext::transfer(self, to, value)
+    }
+
+    fn deposit_event(&mut self, topics: Vec<TopicOf<Self::T>>, data: Vec<u8>) {
+        Ext::deposit_event(self, topics, data)
+    }
+
+    fn get_storage(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        let key = storage_key::<Self::T>(key).ok()?;
+        Ext::get_storage(self, &key)
+    }
+
+    fn set_storage(&mut self, key: &[u8], value: Option<Vec<u8>>) -> DispatchResult {
+        let key = storage_key::<Self::T>(key)?;
+        Ext::set_storage(self, &key, value, false).map(|_| ())
+    }
+}
+
+/// Check that the provided key fits the maximum storage key length.
+pub(crate) fn check_storage_key<T: Config>(key: &[u8]) -> DispatchResult {
+    if key.len() > <T as Config>::MaxStorageKeyLen::get() as usize {
+        return Err(DispatchError::from(Error::<T>::DecodingFailed))
+    }
+
+    Ok(())
+}
+
+fn storage_key<T: Config>(key: &[u8]) -> Result<Key<T>, DispatchError> {
+    Key::try_from_var(key.to_vec()).map_err(|_| DispatchError::from(Error::<T>::DecodingFailed))
+}
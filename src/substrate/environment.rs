@@ -95,6 +95,14 @@ pub trait ChainExtensionEnvironment<E, T> {
     /// refunded to match the actual amount.
     fn adjust_weight(&mut self, charged: Self::ChargedAmount, actual_weight: Weight);
 
+    /// Get the remaining weight budget of the current contract call.
+    ///
+    /// The default implementation reports no remaining weight, so that environments
+    /// that don't track their weight budget fail closed instead of reporting an unlimited one.
+    fn remaining_weight(&mut self) -> Weight {
+        Weight::zero()
+    }
+
     /// Grants access to the execution environment of the current contract call.
     ///
     /// Consult the functions on the returned type before re-implementing those functions.
@@ -148,6 +156,10 @@ where
         Environment::adjust_weight(self, charged, actual_weight)
     }
 
+    fn remaining_weight(&mut self) -> Weight {
+        Environment::ext(self).gas_meter().gas_left()
+    }
+
     fn ext(&mut self) -> &mut E {
         Environment::ext(self)
     }
@@ -0,0 +1,105 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use core::marker::PhantomData;
+
+use scale::DecodeLimit;
+use sp_core::{
+    Decode,
+    MaxEncodedLen,
+};
+use sp_runtime::DispatchError;
+
+use crate::substrate::{
+    ChainExtensionEnvironment,
+    CriticalError,
+};
+
+/// Critical error that is returned when the chain extension input is longer than allowed.
+pub const INPUT_LENGTH_EXCEEDED: CriticalError =
+    DispatchError::Other("chain extension input exceeds the maximum length");
+
+/// Critical error that is returned when the chain extension input can't be decoded
+/// within the allowed nesting depth.
+pub const INPUT_DEPTH_EXCEEDED: CriticalError =
+    DispatchError::Other("chain extension input is malformed or exceeds the maximum depth");
+
+/// Chain extension input reader, that chooses the decoding strategy based on the input type.
+///
+/// Inputs that implement [`MaxEncodedLen`] are read using [`ReadBoundedInput`], while the
+/// rest of the inputs fall back to [`ReadUnboundedInput`].
+#[doc(hidden)]
+pub struct InputReader<I>(PhantomData<I>);
+
+impl<I> InputReader<I> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        InputReader(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait ReadBoundedInput<I> {
+    fn read_input<E, T, Env: ChainExtensionEnvironment<E, T>>(
+        &self,
+        env: &mut Env,
+        len: u32,
+    ) -> Result<I, CriticalError>;
+}
+
+impl<I: Decode + MaxEncodedLen> ReadBoundedInput<I> for InputReader<I> {
+    fn read_input<E, T, Env: ChainExtensionEnvironment<E, T>>(
+        &self,
+        env: &mut Env,
+        len: u32,
+    ) -> Result<I, CriticalError> {
+        if len as usize > I::max_encoded_len() {
+            return Err(INPUT_LENGTH_EXCEEDED)
+        }
+
+        env.read_as()
+    }
+}
+
+#[doc(hidden)]
+pub trait ReadUnboundedInput<I> {
+    fn read_input<E, T, Env: ChainExtensionEnvironment<E, T>>(
+        &self,
+        env: &mut Env,
+        len: u32,
+    ) -> Result<I, CriticalError>;
+}
+
+impl<I: Decode> ReadUnboundedInput<I> for &InputReader<I> {
+    fn read_input<E, T, Env: ChainExtensionEnvironment<E, T>>(
+        &self,
+        env: &mut Env,
+        len: u32,
+    ) -> Result<I, CriticalError> {
+        env.read_as_unbounded(len)
+    }
+}
+
+/// Decode chain extension input, limiting its nesting depth.
+#[doc(hidden)]
+pub fn decode_with_depth_limit<I: Decode>(bytes: &[u8], max_depth: u32) -> Result<I, CriticalError> {
+    I::decode_all_with_depth_limit(max_depth, &mut &bytes[..]).map_err(|_| INPUT_DEPTH_EXCEEDED)
+}
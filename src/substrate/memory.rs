@@ -0,0 +1,102 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+//! In-memory contract input, that is shared by the testing and benchmarking environments.
+
+use core::cell::Cell;
+
+use pallet_contracts::chain_extension::Result;
+use scale::DecodeLimit;
+use sp_core::{
+    Decode,
+    MaxEncodedLen,
+};
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+
+use crate::substrate::CriticalError;
+
+/// Maximum input decoding depth, which mirrors the limit that is used by `pallet-contracts`.
+const MAX_DECODE_NESTING: u32 = 256;
+
+/// Error that is returned when reading or writing outside of the provided buffers.
+pub const OUT_OF_BOUNDS: CriticalError = DispatchError::Other("OutOfBounds");
+
+/// Error that is returned when the chain extension input can't be decoded.
+pub const DECODING_FAILED: CriticalError = DispatchError::Other("DecodingFailed");
+
+/// Contract input buffer, that is read the same way `pallet-contracts` reads contract memory.
+pub(crate) struct MemoryInput {
+    bytes: Vec<u8>,
+    read: Cell<bool>,
+}
+
+impl MemoryInput {
+    pub(crate) fn new(bytes: Vec<u8>) -> Self {
+        MemoryInput {
+            bytes,
+            read: Cell::new(false),
+        }
+    }
+
+    pub(crate) fn len(&self) -> u32 {
+        self.bytes.len() as u32
+    }
+
+    /// Check whether any input bytes were read.
+    #[cfg(feature = "std")]
+    pub(crate) fn was_read(&self) -> bool {
+        self.read.get()
+    }
+
+    pub(crate) fn read(&self, max_len: u32) -> &[u8] {
+        let len = self.bytes.len().min(max_len as usize);
+
+        if len > 0 {
+            self.read.set(true);
+        }
+
+        &self.bytes[..len]
+    }
+
+    pub(crate) fn read_into(&self, buffer: &mut &mut [u8]) {
+        let input = self.read(buffer.len() as u32);
+        let sliced = &mut core::mem::take(buffer)[..input.len()];
+        sliced.copy_from_slice(input);
+        *buffer = sliced;
+    }
+
+    pub(crate) fn read_as<U: Decode + MaxEncodedLen>(&self) -> Result<U> {
+        let input = self.read(U::max_encoded_len() as u32);
+
+        U::decode_all_with_depth_limit(MAX_DECODE_NESTING, &mut &input[..]).map_err(|_| DECODING_FAILED)
+    }
+
+    pub(crate) fn read_as_unbounded<U: Decode>(&self, len: u32) -> Result<U> {
+        if len > self.len() {
+            return Err(OUT_OF_BOUNDS)
+        }
+
+        let input = self.read(len);
+
+        U::decode_all_with_depth_limit(MAX_DECODE_NESTING, &mut &input[..]).map_err(|_| DECODING_FAILED)
+    }
+}
@@ -19,12 +19,31 @@
 // OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+mod contract_ext;
 mod environment;
+mod input;
 mod is_critical_error;
+#[cfg(any(feature = "std", feature = "runtime-benchmarks"))]
+mod memory;
+#[cfg(feature = "std")]
+pub mod testing;
 
+pub use contract_ext::ContractExt;
 pub use environment::ChainExtensionEnvironment;
+#[cfg(feature = "runtime-benchmarks")]
+pub use frame_benchmarking;
 pub use frame_support;
 pub use frame_system;
+pub use input::{
+    decode_with_depth_limit,
+    InputReader,
+    ReadBoundedInput,
+    ReadUnboundedInput,
+    INPUT_DEPTH_EXCEEDED,
+    INPUT_LENGTH_EXCEEDED,
+};
 pub use is_critical_error::{
     ToCriticalErr,
     ToCriticalErrFallback,
@@ -36,8 +55,10 @@ pub use sp_std;
 
 use core::marker::PhantomData;
 
+use frame_support::dispatch::Weight;
 use pallet_contracts::chain_extension::RetVal;
 use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
 
 /// Callable chain extension with generalized environment information.
 ///
@@ -73,6 +94,8 @@ where
 
     pre_charged: Option<Env::ChargedAmount>,
 
+    charges: Vec<(Env::ChargedAmount, Weight)>,
+
     _ghost: PhantomData<(E, T)>,
 }
 
@@ -85,6 +108,7 @@ where
             env,
             storage,
             pre_charged,
+            charges: Vec::new(),
             _ghost: PhantomData,
         }
     }
@@ -97,6 +121,61 @@ where
     pub fn pre_charged(&mut self) -> Option<Env::ChargedAmount> {
         self.pre_charged.take()
     }
+
+    /// Charge the passed `amount` of weight, adding it to the running total.
+    ///
+    /// Unlike [`ChainExtensionEnvironment::charge_weight`], the charged amount handle
+    /// is kept by the context, so that it can be partially or fully refunded later
+    /// via [`refund`](Self::refund).
+    pub fn charge(&mut self, amount: Weight) -> Result<(), DispatchError> {
+        let charged = self.env.charge_weight(amount)?;
+        self.charges.push((charged, amount));
+        Ok(())
+    }
+
+    /// Get the remaining weight budget of the current contract call.
+    ///
+    /// Environments that don't override [`ChainExtensionEnvironment::remaining_weight`]
+    /// report zero remaining weight.
+    pub fn remaining(&mut self) -> Weight {
+        self.env.remaining_weight()
+    }
+
+    /// Get the total amount of weight charged via [`charge`](Self::charge),
+    /// excluding the refunded weight.
+    pub fn charged_so_far(&self) -> Weight {
+        self.charges
+            .iter()
+            .fold(Weight::zero(), |total, (_, amount)| total.saturating_add(*amount))
+    }
+
+    /// Refund the passed `amount` of weight, that was previously charged via [`charge`](Self::charge).
+    ///
+    /// The most recent charges are refunded first. Refunds are capped by [`charged_so_far`](Self::charged_so_far)
+    /// for each weight component, thus refunding more weight than was charged is not possible.
+    pub fn refund(&mut self, amount: Weight) -> Result<(), DispatchError> {
+        let mut to_refund = amount;
+        let mut left = Weight::zero();
+
+        while to_refund.any_gt(Weight::zero()) {
+            let Some((charged, charged_amount)) = self.charges.pop() else {
+                break
+            };
+
+            left = left.saturating_add(charged_amount.saturating_sub(to_refund));
+            to_refund = to_refund.saturating_sub(charged_amount);
+
+            self.env.adjust_weight(charged, Weight::zero());
+        }
+
+        // Charged amount handles can be adjusted only once, so the whole amount is refunded
+        // and the rest is charged again to allow further refunds.
+        if left.any_gt(Weight::zero()) {
+            self.charge(left)?;
+        }
+
+        Ok(())
+    }
 }
 
 pub type CriticalError = DispatchError;
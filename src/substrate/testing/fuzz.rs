@@ -0,0 +1,216 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+//! Fuzzing utilities for Substrate chain extension implementations.
+//!
+//! Decoding untrusted contract input is the main attack surface of a chain extension.
+//! Utilities from this module feed arbitrary inputs and method identifiers through
+//! [`CallableChainExtension::call`] using [`MockEnvironment`], checking that:
+//!
+//! * the chain extension doesn't panic;
+//! * the output always fits the output buffer;
+//! * weight is charged before any input bytes are read, unless
+//!   [`FuzzConfig::require_charge_before_read`] is disabled.
+
+use arbitrary::Unstructured;
+use frame_support::dispatch::Weight;
+use sp_std::vec::Vec;
+
+use crate::{
+    codegen::ExtensionDescription,
+    substrate::{
+        testing::{
+            MockEnvironment,
+            DEFAULT_OUTPUT_LEN,
+            OUTPUT_BUFFER_TOO_SMALL,
+            SENTINEL,
+        },
+        CallableChainExtension,
+    },
+};
+
+/// Fuzzing configuration.
+#[derive(Debug, Clone)]
+pub struct FuzzConfig {
+    /// Output buffer length that is provided to the chain extension.
+    ///
+    /// Defaults to [`DEFAULT_OUTPUT_LEN`], which is the default ink! static buffer size.
+    pub output_len: u32,
+
+    /// Whether the chain extension is required to charge weight before reading any input bytes.
+    ///
+    /// Enabled by default. [`#[obce::implementation]`](macro@crate::implementation) dispatch charges
+    /// the method weight before reading the method input, unless the weight depends on method arguments,
+    /// which can only be computed after decoding them. Such methods should be covered by `base_weight`
+    /// or `per_input_byte` weight, otherwise this check fails for them.
+    pub require_charge_before_read: bool,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        FuzzConfig {
+            output_len: DEFAULT_OUTPUT_LEN,
+            require_charge_before_read: true,
+        }
+    }
+}
+
+/// Single fuzzing case, that describes a chain extension call made by a contract.
+#[derive(Debug, Clone)]
+pub struct FuzzCase {
+    /// Method identifier.
+    pub func_id: u16,
+
+    /// Raw method input.
+    pub input: Vec<u8>,
+
+    /// Whether the contract provided [`SENTINEL`] as an output pointer.
+    pub skip_output: bool,
+
+    /// Weight limit of the call.
+    pub weight_limit: Weight,
+}
+
+impl FuzzCase {
+    /// Build a fuzzing case from the fuzzer-provided data.
+    ///
+    /// Method identifiers are mostly chosen among the identifiers of the chain extension
+    /// described by `D` to reach the method implementations more often.
+    pub fn new<D>(data: &mut Unstructured) -> arbitrary::Result<Self>
+    where
+        D: ExtensionDescription + ?Sized,
+    {
+        let func_id = if !D::METHOD_IDS.is_empty() && data.ratio(3, 4)? {
+            *data.choose(D::METHOD_IDS)?
+        } else {
+            data.arbitrary()?
+        };
+
+        let skip_output = data.ratio(1, 8)?;
+        let weight_limit = Weight::from_parts(data.arbitrary()?, data.arbitrary()?);
+        let input = data.arbitrary()?;
+
+        Ok(FuzzCase {
+            func_id,
+            input,
+            skip_output,
+            weight_limit,
+        })
+    }
+}
+
+/// Build a fuzzing case from the fuzzer-provided data and [`check`] it.
+///
+/// Data that is not sufficient to build a fuzzing case is ignored.
+pub fn run<D, X, E, T>(extension: &mut X, ext: E, data: &[u8], config: FuzzConfig)
+where
+    D: ExtensionDescription + ?Sized,
+    for<'a> X: CallableChainExtension<E, T, &'a mut MockEnvironment<E, T>>,
+{
+    if let Ok(case) = FuzzCase::new::<D>(&mut Unstructured::new(data)) {
+        check::<D, X, E, T>(extension, ext, case, config);
+    }
+}
+
+/// Call the chain extension described by `D` with the provided fuzzing case,
+/// returning the environment for further inspection.
+///
+/// # Panics
+///
+/// This function panics (in addition to any panic that happened inside of the chain extension) if
+/// the chain extension produces an output that doesn't fit the output buffer, or if it reads
+/// input bytes before charging weight (unless [`FuzzConfig::require_charge_before_read`] is disabled).
+///
+/// Critical errors, such as running out of gas or failing to decode the input, are expected
+/// outcomes for arbitrary inputs and are not treated as failures.
+pub fn check<D, X, E, T>(extension: &mut X, ext: E, case: FuzzCase, config: FuzzConfig) -> MockEnvironment<E, T>
+where
+    D: ExtensionDescription + ?Sized,
+    for<'a> X: CallableChainExtension<E, T, &'a mut MockEnvironment<E, T>>,
+{
+    let FuzzCase {
+        func_id,
+        input,
+        skip_output,
+        weight_limit,
+    } = case;
+
+    let mut env = MockEnvironment::new(ext)
+        .with_ids(D::ID, func_id)
+        .with_input(input)
+        .with_output_len(config.output_len)
+        .with_weight_limit(weight_limit);
+
+    if skip_output {
+        env = env.with_output_ptr(SENTINEL);
+    }
+
+    let result = <X as CallableChainExtension<E, T, &mut MockEnvironment<E, T>>>::call(extension, &mut env);
+
+    assert!(
+        !matches!(result, Err(error) if error == OUTPUT_BUFFER_TOO_SMALL),
+        "chain extension output doesn't fit the output buffer of {} bytes (method {func_id})",
+        config.output_len,
+    );
+
+    if config.require_charge_before_read {
+        assert!(
+            !env.read_before_charge(),
+            "chain extension read the input before charging weight (method {func_id})"
+        );
+    }
+
+    env
+}
+
+/// Generate a `cargo fuzz` target for the provided chain extension.
+///
+/// The target is built on top of `libfuzzer-sys`, which should be added as a dependency
+/// of your fuzzing crate. You can optionally provide an `Ext` value (`()` by default) and a
+/// [`FuzzConfig`] expression.
+///
+/// # Example
+///
+/// ```ignore
+/// #![no_main]
+///
+/// obce::fuzz_target!(dyn ChainExtensionDefinition, ChainExtension::default(), Runtime);
+/// ```
+#[macro_export]
+macro_rules! fuzz_target {
+    ($definition:ty, $extension:expr, $runtime:ty $(,)?) => {
+        $crate::fuzz_target!($definition, $extension, $runtime, ());
+    };
+    ($definition:ty, $extension:expr, $runtime:ty, $ext:expr $(,)?) => {
+        $crate::fuzz_target!(
+            $definition,
+            $extension,
+            $runtime,
+            $ext,
+            $crate::substrate::testing::fuzz::FuzzConfig::default()
+        );
+    };
+    ($definition:ty, $extension:expr, $runtime:ty, $ext:expr, $config:expr $(,)?) => {
+        ::libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+            $crate::substrate::testing::fuzz::run::<$definition, _, _, $runtime>(&mut $extension, $ext, data, $config);
+        });
+    };
+}
@@ -0,0 +1,572 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+//! Utilities for unit-testing Substrate chain extension implementations.
+
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+
+pub use crate::substrate::memory::{
+    DECODING_FAILED,
+    OUT_OF_BOUNDS,
+};
+
+use core::{
+    cell::Cell,
+    marker::PhantomData,
+};
+use std::collections::BTreeMap;
+
+use frame_support::{
+    dispatch::Weight,
+    traits::{
+        Currency,
+        Time,
+    },
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_contracts::{
+    chain_extension::{
+        Result,
+        RetVal,
+    },
+    Config,
+    Origin,
+};
+use scale::{
+    Decode,
+    Encode,
+};
+use sp_core::MaxEncodedLen;
+use sp_runtime::{
+    traits::{
+        Saturating,
+        Zero,
+    },
+    DispatchError,
+    DispatchResult,
+};
+use sp_std::vec::Vec;
+
+use crate::{
+    codegen::{
+        ExtensionDescription,
+        MethodDescription,
+    },
+    substrate::{
+        contract_ext::check_storage_key,
+        memory::MemoryInput,
+        CallableChainExtension,
+        ChainExtensionEnvironment,
+        ContractExt,
+        CriticalError,
+    },
+};
+
+/// Output pointer value, that contracts use to signal that they are not interested in the output.
+///
+/// Mirrors the value that is used by `pallet-contracts`.
+pub const SENTINEL: u32 = u32::MAX;
+
+/// Default output buffer length, which is equal to the default ink! static buffer size.
+pub const DEFAULT_OUTPUT_LEN: u32 = 1 << 14;
+
+/// Error that is returned when the charged weight exceeds the weight limit.
+pub const OUT_OF_GAS: CriticalError = DispatchError::Other("OutOfGas");
+
+/// Error that is returned when the output doesn't fit the output buffer.
+pub const OUTPUT_BUFFER_TOO_SMALL: CriticalError = DispatchError::Other("OutputBufferTooSmall");
+
+/// Error that is returned when the transfer can't be made due to insufficient contract balance.
+pub const INSUFFICIENT_BALANCE: DispatchError = DispatchError::Other("InsufficientBalance");
+
+type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+type BalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::Balance;
+type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
+type TopicOf<T> = <T as frame_system::Config>::Hash;
+
+/// In-memory [`ChainExtensionEnvironment`] implementation for unit-testing.
+///
+/// [`MockEnvironment`] mimics the behaviour of `pallet-contracts` environment: it enforces
+/// the weight limit and the output buffer length, skips the output if the contract provided
+/// [`SENTINEL`] as an output pointer, and records charged weights, weight adjustments and writes,
+/// so that you can inspect them after the call.
+///
+/// [`ChainExtensionEnvironment`] is implemented for `&mut MockEnvironment`, which allows you
+/// to keep the environment after passing it to [`CallableChainExtension::call`].
+///
+/// # Example
+///
+/// ```ignore
+/// let mut env = MockEnvironment::<_, Runtime>::new(())
+///     .with_weight_limit(Weight::from_parts(1_000_000, 0));
+///
+/// let output = env.call::<dyn ChainExtensionDefinition, { obce::method_hash!(ChainExtensionDefinition::method) }, _>(
+///     &mut ChainExtension,
+///     (1, 2),
+/// );
+///
+/// assert_eq!(output, Ok(3));
+/// assert_eq!(env.charged_weights(), &[Weight::from_parts(100, 0)]);
+/// ```
+pub struct MockEnvironment<E, T> {
+    ext_id: u16,
+    func_id: u16,
+    input: MemoryInput,
+    output_ptr: u32,
+    max_output_len: u32,
+    output_len: u32,
+    writes: Vec<Vec<u8>>,
+    weight_limit: Weight,
+    weight_left: Weight,
+    charged_weights: Vec<Weight>,
+    adjusted_weights: Vec<(Weight, Weight)>,
+    read_before_charge: Cell<bool>,
+    ext: E,
+    _ghost: PhantomData<T>,
+}
+
+impl<E, T> MockEnvironment<E, T> {
+    /// Create a new environment with the provided `Ext` value.
+    ///
+    /// The environment has an unlimited weight budget and [`DEFAULT_OUTPUT_LEN`] output buffer length.
+    pub fn new(ext: E) -> Self {
+        MockEnvironment {
+            ext_id: 0,
+            func_id: 0,
+            input: MemoryInput::new(Vec::new()),
+            output_ptr: 0,
+            max_output_len: DEFAULT_OUTPUT_LEN,
+            output_len: DEFAULT_OUTPUT_LEN,
+            writes: Vec::new(),
+            weight_limit: Weight::MAX,
+            weight_left: Weight::MAX,
+            charged_weights: Vec::new(),
+            adjusted_weights: Vec::new(),
+            read_before_charge: Cell::new(false),
+            ext,
+            _ghost: PhantomData,
+        }
+    }
+
+    /// Set the chain extension and method identifiers.
+    pub fn with_ids(mut self, ext_id: u16, func_id: u16) -> Self {
+        self.ext_id = ext_id;
+        self.func_id = func_id;
+        self
+    }
+
+    /// Set the raw input buffer.
+    pub fn with_input(mut self, input: Vec<u8>) -> Self {
+        self.input = MemoryInput::new(input);
+        self
+    }
+
+    /// Set the output buffer length.
+    pub fn with_output_len(mut self, output_len: u32) -> Self {
+        self.max_output_len = output_len;
+        self.output_len = output_len;
+        self
+    }
+
+    /// Set the output pointer, which can be equal to [`SENTINEL`] to skip the output.
+    pub fn with_output_ptr(mut self, output_ptr: u32) -> Self {
+        self.output_ptr = output_ptr;
+        self
+    }
+
+    /// Set the weight limit.
+    pub fn with_weight_limit(mut self, weight_limit: Weight) -> Self {
+        self.weight_limit = weight_limit;
+        self.weight_left = weight_limit;
+        self
+    }
+
+    /// Get all outputs written by the chain extension.
+    pub fn writes(&self) -> &[Vec<u8>] {
+        &self.writes
+    }
+
+    /// Get the last output written by the chain extension.
+    pub fn output(&self) -> Option<&[u8]> {
+        self.writes.last().map(Vec::as_slice)
+    }
+
+    /// Get all weights charged by the chain extension, in the order of charging.
+    pub fn charged_weights(&self) -> &[Weight] {
+        &self.charged_weights
+    }
+
+    /// Get all weight adjustments made by the chain extension as `(charged, actual)` pairs.
+    pub fn adjusted_weights(&self) -> &[(Weight, Weight)] {
+        &self.adjusted_weights
+    }
+
+    /// Get the total weight consumed by the chain extension, including the adjustments.
+    pub fn consumed_weight(&self) -> Weight {
+        self.weight_limit.saturating_sub(self.weight_left)
+    }
+
+    /// Check whether the chain extension has read any input bytes before charging weight.
+    pub fn read_before_charge(&self) -> bool {
+        self.read_before_charge.get()
+    }
+
+    /// Get the `Ext` value.
+    pub fn ext(&mut self) -> &mut E {
+        &mut self.ext
+    }
+
+    /// Call the chain extension method, that is described by `D` and `METHOD` generics,
+    /// with the provided input, returning the decoded output.
+    ///
+    /// `METHOD` is the method hash, which can be obtained using [`obce::method_hash!`](macro@crate::method_hash).
+    ///
+    /// Outputs, charged weights, weight adjustments and the weight budget are reset before the call,
+    /// so that the environment can be reused for multiple calls, while inspection methods
+    /// (such as [`charged_weights`](Self::charged_weights)) report the last call only.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the chain extension returns a non-zero status code, or
+    /// if the written output can't be decoded as the method output type. You can use
+    /// [`CallableChainExtension::call`] directly to test such cases.
+    pub fn call<D, const METHOD: u32, X>(
+        &mut self,
+        extension: &mut X,
+        input: <D as MethodDescription<METHOD>>::Input,
+    ) -> core::result::Result<<D as MethodDescription<METHOD>>::Output, CriticalError>
+    where
+        D: ExtensionDescription + MethodDescription<METHOD> + ?Sized,
+        <D as MethodDescription<METHOD>>::Input: Encode,
+        <D as MethodDescription<METHOD>>::Output: Decode,
+        for<'a> X: CallableChainExtension<E, T, &'a mut Self>,
+    {
+        self.reset();
+        self.ext_id = <D as ExtensionDescription>::ID;
+        self.func_id = <D as MethodDescription<METHOD>>::ID;
+        self.input = MemoryInput::new(input.encode());
+
+        match <X as CallableChainExtension<E, T, &mut Self>>::call(extension, &mut *self)? {
+            RetVal::Converging(0) => {}
+            RetVal::Converging(status_code) => panic!("chain extension returned {status_code} status code"),
+            RetVal::Diverging { .. } => panic!("chain extension returned a diverging value"),
+        }
+
+        let output = self.output().unwrap_or_default();
+
+        Ok(Decode::decode(&mut &output[..]).expect("unable to decode chain extension output"))
+    }
+
+    /// Reset the state of the previous call, keeping the configuration and the `Ext` value.
+    fn reset(&mut self) {
+        self.output_len = self.max_output_len;
+        self.writes.clear();
+        self.weight_left = self.weight_limit;
+        self.charged_weights.clear();
+        self.adjusted_weights.clear();
+        self.read_before_charge.set(false);
+    }
+
+    /// Record whether the input was read before any weight was charged.
+    fn track_read<R>(&self, result: R) -> R {
+        if self.input.was_read() && self.charged_weights.is_empty() {
+            self.read_before_charge.set(true);
+        }
+
+        result
+    }
+}
+
+impl<'a, E, T> ChainExtensionEnvironment<E, T> for &'a mut MockEnvironment<E, T> {
+    type ChargedAmount = Weight;
+
+    fn func_id(&self) -> u16 {
+        self.func_id
+    }
+
+    fn ext_id(&self) -> u16 {
+        self.ext_id
+    }
+
+    fn in_len(&self) -> u32 {
+        self.input.len()
+    }
+
+    fn read(&self, max_len: u32) -> Result<Vec<u8>> {
+        Ok(self.track_read(self.input.read(max_len).to_vec()))
+    }
+
+    fn read_into(&self, buffer: &mut &mut [u8]) -> Result<()> {
+        self.input.read_into(buffer);
+        self.track_read(Ok(()))
+    }
+
+    fn read_as<U: Decode + MaxEncodedLen>(&mut self) -> Result<U> {
+        self.track_read(self.input.read_as())
+    }
+
+    fn read_as_unbounded<U: Decode>(&mut self, len: u32) -> Result<U> {
+        self.track_read(self.input.read_as_unbounded(len))
+    }
+
+    fn write(&mut self, buffer: &[u8], allow_skip: bool, weight_per_byte: Option<Weight>) -> Result<()> {
+        if self.output_ptr == SENTINEL {
+            return if allow_skip { Ok(()) } else { Err(OUT_OF_BOUNDS) }
+        }
+
+        if buffer.len() > self.output_len as usize {
+            return Err(OUTPUT_BUFFER_TOO_SMALL)
+        }
+
+        if let Some(weight_per_byte) = weight_per_byte {
+            self.charge_weight(weight_per_byte.saturating_mul(buffer.len() as u64))?;
+        }
+
+        // Similarly to `pallet-contracts`, the output length is updated with the written length.
+        self.output_len = buffer.len() as u32;
+        self.writes.push(buffer.to_vec());
+
+        Ok(())
+    }
+
+    fn charge_weight(&mut self, amount: Weight) -> Result<Self::ChargedAmount> {
+        if self.weight_left.any_lt(amount) {
+            return Err(OUT_OF_GAS)
+        }
+
+        self.weight_left = self.weight_left.saturating_sub(amount);
+        self.charged_weights.push(amount);
+
+        Ok(amount)
+    }
+
+    fn adjust_weight(&mut self, charged: Self::ChargedAmount, actual_weight: Weight) {
+        let adjustment = charged.saturating_sub(actual_weight);
+        self.weight_left = self.weight_left.saturating_add(adjustment).min(self.weight_limit);
+        self.adjusted_weights.push((charged, actual_weight));
+    }
+
+    fn remaining_weight(&mut self) -> Weight {
+        self.weight_left
+    }
+
+    fn ext(&mut self) -> &mut E {
+        &mut self.ext
+    }
+}
+
+/// Configurable [`ContractExt`] implementation for unit-testing.
+///
+/// `pallet_contracts::chain_extension::Ext` is sealed and can't be implemented outside of
+/// `pallet-contracts`, so to use [`MockExt`] your implementation should be bounded by
+/// `E: ContractExt<T = T>` instead of `E: Ext<T = T>`.
+///
+/// Balances are tracked for every account, with transfers being made from the contract address
+/// while keeping at least the minimum balance on it. Deposited events, successful transfers
+/// and contract storage are recorded, so that you can inspect them after the call.
+///
+/// # Example
+///
+/// ```ignore
+/// let ext = MockExt::<Runtime>::new(ALICE, CONTRACT)
+///     .with_balance(CONTRACT, 1000)
+///     .with_block_number(10);
+///
+/// let mut env = MockEnvironment::<_, Runtime>::new(ext);
+///
+/// env.call::<dyn ChainExtensionDefinition, { obce::method_hash!(ChainExtensionDefinition::pay) }, _>(
+///     &mut ChainExtension,
+///     (BOB, 100),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(env.ext().transfers(), &[(CONTRACT, BOB, 100)]);
+/// assert_eq!(env.ext().balance_of(&CONTRACT), 900);
+/// ```
+pub struct MockExt<T: Config> {
+    caller: Origin<T>,
+    address: AccountIdOf<T>,
+    value_transferred: BalanceOf<T>,
+    minimum_balance: BalanceOf<T>,
+    balances: BTreeMap<AccountIdOf<T>, BalanceOf<T>>,
+    block_number: BlockNumberFor<T>,
+    now: MomentOf<T>,
+    events: Vec<(Vec<TopicOf<T>>, Vec<u8>)>,
+    transfers: Vec<(AccountIdOf<T>, AccountIdOf<T>, BalanceOf<T>)>,
+    storage: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl<T: Config> MockExt<T> {
+    /// Create a new `Ext` with the provided caller and contract address.
+    ///
+    /// All balances, block number and timestamp are initialized with zeroes.
+    pub fn new(caller: AccountIdOf<T>, address: AccountIdOf<T>) -> Self {
+        MockExt {
+            caller: Origin::Signed(caller),
+            address,
+            value_transferred: Zero::zero(),
+            minimum_balance: Zero::zero(),
+            balances: BTreeMap::new(),
+            block_number: Zero::zero(),
+            now: Zero::zero(),
+            events: Vec::new(),
+            transfers: Vec::new(),
+            storage: BTreeMap::new(),
+        }
+    }
+
+    /// Set the caller origin, which can be used to emulate root calls.
+    pub fn with_caller(mut self, caller: Origin<T>) -> Self {
+        self.caller = caller;
+        self
+    }
+
+    /// Set the contract address.
+    pub fn with_address(mut self, address: AccountIdOf<T>) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Set the value transferred to the contract.
+    pub fn with_value_transferred(mut self, value: BalanceOf<T>) -> Self {
+        self.value_transferred = value;
+        self
+    }
+
+    /// Set the minimum balance that an account can hold.
+    pub fn with_minimum_balance(mut self, minimum_balance: BalanceOf<T>) -> Self {
+        self.minimum_balance = minimum_balance;
+        self
+    }
+
+    /// Set the balance of the provided account.
+    pub fn with_balance(mut self, account: AccountIdOf<T>, balance: BalanceOf<T>) -> Self {
+        self.balances.insert(account, balance);
+        self
+    }
+
+    /// Set the current block number.
+    pub fn with_block_number(mut self, block_number: BlockNumberFor<T>) -> Self {
+        self.block_number = block_number;
+        self
+    }
+
+    /// Set the current timestamp.
+    pub fn with_timestamp(mut self, now: MomentOf<T>) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// Set the contract storage entry.
+    pub fn with_storage(mut self, key: Vec<u8>, value: Vec<u8>) -> Self {
+        self.storage.insert(key, value);
+        self
+    }
+
+    /// Get the balance of the provided account.
+    pub fn balance_of(&self, account: &AccountIdOf<T>) -> BalanceOf<T> {
+        self.balances.get(account).copied().unwrap_or_else(Zero::zero)
+    }
+
+    /// Get all events deposited by the chain extension as `(topics, data)` pairs.
+    pub fn events(&self) -> &[(Vec<TopicOf<T>>, Vec<u8>)] {
+        &self.events
+    }
+
+    /// Get all transfers made by the chain extension as `(from, to, value)` triples.
+    pub fn transfers(&self) -> &[(AccountIdOf<T>, AccountIdOf<T>, BalanceOf<T>)] {
+        &self.transfers
+    }
+
+    /// Get the contract storage.
+    pub fn storage(&self) -> &BTreeMap<Vec<u8>, Vec<u8>> {
+        &self.storage
+    }
+}
+
+impl<T: Config> ContractExt for MockExt<T> {
+    type T = T;
+
+    fn caller(&self) -> Origin<T> {
+        self.caller.clone()
+    }
+
+    fn address(&self) -> &AccountIdOf<T> {
+        &self.address
+    }
+
+    fn balance(&self) -> BalanceOf<T> {
+        self.balance_of(&self.address)
+    }
+
+    fn value_transferred(&self) -> BalanceOf<T> {
+        self.value_transferred
+    }
+
+    fn now(&self) -> &MomentOf<T> {
+        &self.now
+    }
+
+    fn minimum_balance(&self) -> BalanceOf<T> {
+        self.minimum_balance
+    }
+
+    fn block_number(&self) -> BlockNumberFor<T> {
+        self.block_number
+    }
+
+    fn transfer(&mut self, to: &AccountIdOf<T>, value: BalanceOf<T>) -> DispatchResult {
+        let balance = self.balance();
+
+        if balance < value.saturating_add(self.minimum_balance) {
+            return Err(INSUFFICIENT_BALANCE)
+        }
+
+        self.balances
+            .insert(self.address.clone(), balance.saturating_sub(value));
+
+        let to_balance = self.balance_of(to);
+        self.balances.insert(to.clone(), to_balance.saturating_add(value));
+        self.transfers.push((self.address.clone(), to.clone(), value));
+
+        Ok(())
+    }
+
+    fn deposit_event(&mut self, topics: Vec<TopicOf<T>>, data: Vec<u8>) {
+        self.events.push((topics, data));
+    }
+
+    fn get_storage(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        self.storage.get(key).cloned()
+    }
+
+    fn set_storage(&mut self, key: &[u8], value: Option<Vec<u8>>) -> DispatchResult {
+        check_storage_key::<T>(key)?;
+
+        match value {
+            Some(value) => self.storage.insert(key.to_vec(), value),
+            None => self.storage.remove(key),
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+#[obce::definition]
+pub trait Trait {
+    type Balance;
+
+    const MAX_BATCH: u32 = 2;
+
+    fn balance_of(&self, account: u32) -> Self::Balance;
+
+    fn transfer(&mut self, to: u32, amount: Self::Balance) -> Option<Self::Balance>;
+}
+
+#[obce::ink_lang::extension]
+struct TestExtension;
+
+impl Trait for TestExtension {
+    type Balance = u128;
+}
+
+#[ink::contract]
+mod simple_contract {
+    use crate::{
+        TestExtension,
+        Trait,
+    };
+
+    #[ink(storage)]
+    pub struct SimpleContract {}
+
+    impl SimpleContract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            SimpleContract {}
+        }
+
+        #[ink(message)]
+        pub fn call_balance_of(&self, account: u32) -> u128 {
+            TestExtension.balance_of(account)
+        }
+
+        #[ink(message)]
+        pub fn call_transfer(&mut self, to: u32, amount: u128) -> Option<u128> {
+            TestExtension.transfer(to, amount)
+        }
+
+        #[ink(message)]
+        pub fn max_batch(&self) -> u32 {
+            <dyn Trait>::MAX_BATCH
+        }
+    }
+}
+
+mod balances {
+    #[derive(Clone, Default)]
+    pub struct State {
+        balances: std::collections::BTreeMap<u32, u128>,
+    }
+
+    #[obce::mock]
+    impl crate::Trait for State {
+        type Balance = u128;
+
+        fn balance_of(&self, account: u32) -> Self::Balance {
+            self.balances.get(&account).copied().unwrap_or_default()
+        }
+
+        fn transfer(&mut self, to: u32, amount: Self::Balance) -> Option<Self::Balance> {
+            let balance = self.balances.entry(to).or_default();
+            *balance = balance.checked_add(amount)?;
+            Some(*balance)
+        }
+    }
+
+    #[test]
+    fn call_contract() {
+        TraitRegistration::register(State::default());
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.call_balance_of(1), 0);
+        assert_eq!(contract.call_transfer(1, 100), Some(100));
+        assert_eq!(contract.call_transfer(1, u128::MAX), None);
+        assert_eq!(contract.call_balance_of(1), 100);
+        assert_eq!(contract.max_batch(), 2);
+    }
+}
@@ -0,0 +1,70 @@
+#[obce::definition(id = 123, version = 2)]
+pub trait Trait {
+    #[obce(id = 1)]
+    fn method(&mut self, val: u32) -> u32;
+
+    #[obce(id = 2, since = 2)]
+    fn new_method(&mut self, val: u32) -> u32;
+}
+
+#[ink::contract]
+mod simple_contract {
+    use ink::prelude::vec::Vec;
+    use obce::ink_lang::CallError;
+
+    use crate::Trait;
+
+    #[ink(storage)]
+    pub struct SimpleContract {}
+
+    impl SimpleContract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            SimpleContract {}
+        }
+
+        #[ink(message)]
+        pub fn version(&self) -> Result<u16, CallError> {
+            obce::ink_lang::extension_version::<dyn Trait>()
+        }
+
+        #[ink(message)]
+        pub fn supported_methods(&self) -> Result<Vec<u16>, CallError> {
+            obce::ink_lang::supported_methods::<dyn Trait>()
+        }
+
+        #[ink(message)]
+        pub fn is_new_method_supported(&self) -> Result<bool, CallError> {
+            obce::ink_lang::is_method_supported::<dyn Trait>(obce::id!(Trait::new_method))
+        }
+
+        #[ink(message)]
+        pub fn abi_hash(&self) -> Result<u32, CallError> {
+            obce::ink_lang::abi_hash::<dyn Trait>()
+        }
+    }
+}
+
+mod handshake {
+    use obce::codegen::ExtensionDescription;
+
+    #[obce::mock]
+    impl crate::Trait for () {
+        fn method(&mut self, val: u32) -> u32 {
+            val
+        }
+    }
+
+    #[test]
+    fn call_contract() {
+        TraitRegistration::register(());
+        let contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.version(), Ok(2));
+        assert_eq!(contract.supported_methods(), Ok(vec![1]));
+        assert_eq!(contract.is_new_method_supported(), Ok(false));
+        assert_eq!(
+            contract.abi_hash(),
+            Ok(<dyn crate::Trait as ExtensionDescription>::ABI_HASH)
+        );
+    }
+}
@@ -63,7 +63,7 @@ mod call_using_env {
 
     #[test]
     fn call_contract() {
-        register_chain_extensions(());
+        TraitRegistration::register(());
         let contract = crate::simple_contract::SimpleContract::new();
         assert_eq!(contract.call_method(), 123);
     }
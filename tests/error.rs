@@ -63,3 +63,21 @@ fn error_macro_with_relaxed_ret_val_works() {
         Err(_)
     ));
 }
+
+#[test]
+fn error_macro_with_unknown_status_works() {
+    #[obce::error]
+    pub enum Error {
+        #[obce(ret_val = "100")]
+        RetValVariant,
+
+        #[obce(unknown_status)]
+        UnknownStatusVariant(u32),
+    }
+
+    assert!(matches!(
+        RetVal::try_from(Error::RetValVariant),
+        Ok(RetVal::Converging(100))
+    ));
+    assert!(matches!(RetVal::try_from(Error::UnknownStatusVariant(200)), Err(_)));
+}
@@ -0,0 +1,104 @@
+mod runtime;
+
+use obce::substrate::{
+    frame_support::dispatch::Weight,
+    testing::{
+        MockEnvironment,
+        OUT_OF_GAS,
+    },
+    ExtensionContext,
+};
+use runtime::Runtime;
+
+type Context<'a> = ExtensionContext<'a, (), Runtime, &'a mut MockEnvironment<(), Runtime>, ()>;
+
+fn env(ref_time_limit: u64) -> MockEnvironment<(), Runtime> {
+    MockEnvironment::new(()).with_weight_limit(Weight::from_parts(ref_time_limit, 1_000))
+}
+
+#[test]
+fn charges_are_accumulated() {
+    let mut env = env(1_000);
+    let mut storage = ();
+    let mut context: Context = ExtensionContext::new(&mut storage, &mut env, None);
+
+    context.charge(Weight::from_parts(100, 0)).unwrap();
+    context.charge(Weight::from_parts(200, 0)).unwrap();
+
+    assert_eq!(context.charged_so_far(), Weight::from_parts(300, 0));
+    assert_eq!(context.remaining(), Weight::from_parts(700, 1_000));
+
+    assert_eq!(
+        env.charged_weights(),
+        &[Weight::from_parts(100, 0), Weight::from_parts(200, 0)]
+    );
+}
+
+#[test]
+fn charge_over_limit_is_rejected() {
+    let mut env = env(100);
+    let mut storage = ();
+    let mut context: Context = ExtensionContext::new(&mut storage, &mut env, None);
+
+    assert_eq!(context.charge(Weight::from_parts(101, 0)), Err(OUT_OF_GAS));
+    assert_eq!(context.charged_so_far(), Weight::zero());
+    assert_eq!(context.remaining(), Weight::from_parts(100, 1_000));
+}
+
+#[test]
+fn partial_refund_keeps_the_rest_charged() {
+    let mut env = env(1_000);
+    let mut storage = ();
+    let mut context: Context = ExtensionContext::new(&mut storage, &mut env, None);
+
+    context.charge(Weight::from_parts(100, 0)).unwrap();
+    context.charge(Weight::from_parts(200, 0)).unwrap();
+    context.refund(Weight::from_parts(250, 0)).unwrap();
+
+    assert_eq!(context.charged_so_far(), Weight::from_parts(50, 0));
+    assert_eq!(context.remaining(), Weight::from_parts(950, 1_000));
+
+    // Refunded charges can be refunded further.
+    context.refund(Weight::from_parts(50, 0)).unwrap();
+
+    assert_eq!(context.charged_so_far(), Weight::zero());
+    assert_eq!(env.consumed_weight(), Weight::zero());
+}
+
+#[test]
+fn refund_is_capped_by_charged_weight() {
+    let mut env = env(1_000);
+    let mut storage = ();
+    let mut context: Context = ExtensionContext::new(&mut storage, &mut env, None);
+
+    context.charge(Weight::from_parts(100, 0)).unwrap();
+    context.refund(Weight::from_parts(500, 500)).unwrap();
+
+    assert_eq!(context.charged_so_far(), Weight::zero());
+    assert_eq!(context.remaining(), Weight::from_parts(1_000, 1_000));
+}
+
+#[test]
+fn refund_is_applied_per_weight_component() {
+    let mut env = env(1_000);
+    let mut storage = ();
+    let mut context: Context = ExtensionContext::new(&mut storage, &mut env, None);
+
+    context.charge(Weight::from_parts(100, 10)).unwrap();
+    context.refund(Weight::from_parts(0, 10)).unwrap();
+
+    assert_eq!(context.charged_so_far(), Weight::from_parts(100, 0));
+    assert_eq!(context.remaining(), Weight::from_parts(900, 1_000));
+}
+
+#[test]
+fn refund_without_charges_does_nothing() {
+    let mut env = env(1_000);
+    let mut storage = ();
+    let mut context: Context = ExtensionContext::new(&mut storage, &mut env, None);
+
+    context.refund(Weight::from_parts(100, 0)).unwrap();
+
+    assert_eq!(context.charged_so_far(), Weight::zero());
+    assert!(env.adjusted_weights().is_empty());
+}
@@ -0,0 +1,203 @@
+mod runtime;
+
+use obce::substrate::{
+    frame_support::dispatch::Weight,
+    frame_system::Config as SysConfig,
+    pallet_contracts::Config as ContractConfig,
+    sp_runtime::traits::StaticLookup,
+    testing::{
+        fuzz::{
+            check,
+            run,
+            FuzzCase,
+            FuzzConfig,
+        },
+        MockEnvironment,
+    },
+    ChainExtensionEnvironment,
+    ExtensionContext,
+};
+use proptest::prelude::*;
+use runtime::Runtime;
+use scale::Encode;
+
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn add(&mut self, a: u32, b: u32) -> u64;
+
+    fn echo(&mut self, data: Vec<u8>) -> Vec<u8>;
+
+    fn pre_charged_add(&mut self, a: u32, b: u32) -> u64;
+}
+
+#[obce::implementation]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    #[obce(weight(expr = "Weight::from_parts(100, 0)"))]
+    fn add(&mut self, a: u32, b: u32) -> u64 {
+        a as u64 + b as u64
+    }
+
+    #[obce(weight(
+        expr = "Weight::from_parts(100 * data.len() as u64, 0)",
+        per_input_byte = "Weight::from_parts(1, 0)"
+    ))]
+    fn echo(&mut self, data: Vec<u8>) -> Vec<u8> {
+        data
+    }
+
+    #[obce(weight(expr = "Weight::from_parts(100, 0)", pre_charge))]
+    fn pre_charged_add(&mut self, a: u32, b: u32) -> u64 {
+        a as u64 + b as u64
+    }
+}
+
+pub struct UnchargedChainExtension;
+
+#[obce::definition]
+pub trait UnchargedChainExtensionDefinition {
+    fn echo(&mut self, data: Vec<u8>) -> Vec<u8>;
+}
+
+#[obce::implementation]
+impl<'a, E, T, Env> UnchargedChainExtensionDefinition for ExtensionContext<'a, E, T, Env, UnchargedChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    // Weight depends on the input, and there is nothing else to charge before reading it.
+    #[obce(weight(expr = "Weight::from_parts(100 * data.len() as u64, 0)"))]
+    fn echo(&mut self, data: Vec<u8>) -> Vec<u8> {
+        data
+    }
+}
+
+fn case(func_id: u16, input: Vec<u8>) -> FuzzCase {
+    FuzzCase {
+        func_id,
+        input,
+        skip_output: false,
+        weight_limit: Weight::from_parts(1_000_000, 0),
+    }
+}
+
+fn check_case(case: FuzzCase, config: FuzzConfig) -> MockEnvironment<(), Runtime> {
+    check::<dyn ChainExtensionDefinition, _, _, Runtime>(&mut ChainExtension, (), case, config)
+}
+
+fn check_uncharged_case(case: FuzzCase, config: FuzzConfig) -> MockEnvironment<(), Runtime> {
+    check::<dyn UnchargedChainExtensionDefinition, _, _, Runtime>(&mut UnchargedChainExtension, (), case, config)
+}
+
+#[test]
+fn well_formed_input_is_dispatched() {
+    let env = check_case(
+        case(obce::id!(ChainExtensionDefinition::add), (1u32, 2u32).encode()),
+        FuzzConfig::default(),
+    );
+
+    assert_eq!(env.output(), Some(&3u64.encode()[..]));
+    assert_eq!(env.charged_weights(), &[Weight::from_parts(100, 0)]);
+}
+
+#[test]
+fn malformed_input_is_rejected() {
+    let env = check_case(
+        case(obce::id!(ChainExtensionDefinition::add), vec![1, 0, 0]),
+        FuzzConfig::default(),
+    );
+
+    // Weight that doesn't depend on the input is charged before decoding it.
+    assert!(env.writes().is_empty());
+    assert_eq!(env.charged_weights(), &[Weight::from_parts(100, 0)]);
+}
+
+#[test]
+fn unknown_method_is_rejected() {
+    let env = check_case(case(u16::MAX, (1u32, 2u32).encode()), FuzzConfig::default());
+
+    assert!(env.writes().is_empty());
+}
+
+#[test]
+fn input_dependent_weight_is_covered_by_per_input_byte() {
+    let env = check_case(
+        case(obce::id!(ChainExtensionDefinition::echo), vec![1, 2, 3].encode()),
+        FuzzConfig::default(),
+    );
+
+    assert!(!env.read_before_charge());
+    assert_eq!(env.output(), Some(&vec![1u8, 2, 3].encode()[..]));
+}
+
+#[test]
+fn pre_charged_method_passes_charge_before_read_check() {
+    let env = check_case(
+        case(
+            obce::id!(ChainExtensionDefinition::pre_charged_add),
+            (1u32, 2u32).encode(),
+        ),
+        FuzzConfig::default(),
+    );
+
+    assert!(!env.read_before_charge());
+    assert_eq!(env.output(), Some(&3u64.encode()[..]));
+}
+
+#[test]
+#[should_panic(expected = "read the input before charging weight")]
+fn read_before_charge_is_detected() {
+    check_uncharged_case(
+        case(
+            obce::id!(UnchargedChainExtensionDefinition::echo),
+            vec![1, 2, 3].encode(),
+        ),
+        FuzzConfig::default(),
+    );
+}
+
+#[test]
+fn charge_before_read_check_can_be_disabled() {
+    let config = FuzzConfig {
+        require_charge_before_read: false,
+        ..FuzzConfig::default()
+    };
+
+    let env = check_uncharged_case(
+        case(
+            obce::id!(UnchargedChainExtensionDefinition::echo),
+            vec![1, 2, 3].encode(),
+        ),
+        config,
+    );
+
+    assert!(env.read_before_charge());
+}
+
+#[test]
+#[should_panic(expected = "doesn't fit the output buffer")]
+fn output_overflow_is_detected() {
+    let config = FuzzConfig {
+        output_len: 4,
+        ..FuzzConfig::default()
+    };
+
+    check_case(
+        case(obce::id!(ChainExtensionDefinition::echo), vec![0u8; 8].encode()),
+        config,
+    );
+}
+
+proptest! {
+    #[test]
+    fn arbitrary_data_doesnt_panic(data in proptest::collection::vec(any::<u8>(), 0..256)) {
+        run::<dyn ChainExtensionDefinition, _, _, Runtime>(&mut ChainExtension, (), &data, FuzzConfig::default());
+    }
+}
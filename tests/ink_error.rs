@@ -0,0 +1,140 @@
+use obce::ink_lang::env::chain_extension::FromStatusCode;
+
+#[obce::error]
+pub enum Error {
+    #[obce(ret_val = "100")]
+    First,
+
+    #[obce(ret_val = "200")]
+    Second,
+}
+
+#[obce::definition(id = 123)]
+pub trait Trait {
+    #[obce(ret_val)]
+    fn method(&mut self, val: u32) -> Result<u32, Error>;
+}
+
+#[obce::ink_lang::extension]
+struct TestExtension;
+
+impl Trait for TestExtension {}
+
+#[ink::contract]
+mod simple_contract {
+    use obce::ink_lang::CallError;
+
+    use crate::{
+        Error,
+        TestExtension,
+        Trait,
+    };
+
+    #[ink(storage)]
+    pub struct SimpleContract {}
+
+    impl SimpleContract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            SimpleContract {}
+        }
+
+        #[ink(message)]
+        pub fn call_method(&mut self, val: u32) -> Result<u32, Error> {
+            TestExtension.method(val)
+        }
+
+        #[ink(message)]
+        pub fn checked_call_method(&mut self, val: u32) -> Result<Result<u32, Error>, CallError> {
+            TestExtension.try_method(val)
+        }
+    }
+}
+
+#[test]
+fn error_macro_status_code_conversion_works() {
+    assert_eq!(Error::from_status_code(0), Ok(()));
+    assert_eq!(Error::from_status_code(100), Err(Error::First));
+    assert_eq!(Error::from_status_code(200), Err(Error::Second));
+}
+
+#[test]
+#[should_panic(expected = "unknown chain extension status code: 300")]
+fn error_macro_unknown_status_code_panics() {
+    let _ = Error::from_status_code(300);
+}
+
+#[test]
+fn error_macro_custom_unknown_status_variant_is_used() {
+    #[obce::error]
+    pub enum CustomError {
+        #[obce(ret_val = "100")]
+        First,
+
+        #[obce(unknown_status)]
+        Other(u32),
+    }
+
+    assert_eq!(CustomError::from_status_code(100), Err(CustomError::First));
+    assert_eq!(CustomError::from_status_code(300), Err(CustomError::Other(300)));
+}
+
+mod ret_val_method {
+    use crate::Error;
+
+    #[obce::mock]
+    impl crate::Trait for () {
+        fn method(&mut self, val: u32) -> Result<u32, Error> {
+            if val == 0 {
+                Err(Error::First)
+            } else {
+                Ok(val)
+            }
+        }
+    }
+
+    #[test]
+    fn call_contract() {
+        TraitRegistration::register(());
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.call_method(123), Ok(123));
+        assert_eq!(contract.call_method(0), Err(Error::First));
+    }
+}
+
+mod unknown_status_code {
+    use obce::ink_lang::{
+        env::test::ChainExtension,
+        CallError,
+    };
+
+    use crate::Trait;
+
+    /// Raw chain extension that returns a status code without a matching error variant.
+    struct UnknownStatusExtension;
+
+    impl ChainExtension for UnknownStatusExtension {
+        fn func_id(&self) -> u32 {
+            (obce::id!(Trait) as u32) << 16 | (obce::id!(Trait::method) as u32)
+        }
+
+        fn call(&mut self, _: &[u8], _: &mut Vec<u8>) -> u32 {
+            300
+        }
+    }
+
+    #[test]
+    fn try_call_contract() {
+        obce::ink_lang::env::test::register_chain_extension(UnknownStatusExtension);
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.checked_call_method(123), Err(CallError::Status(300)));
+    }
+
+    #[test]
+    #[should_panic(expected = "chain extension method call failed")]
+    fn call_contract() {
+        obce::ink_lang::env::test::register_chain_extension(UnknownStatusExtension);
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        let _ = contract.call_method(123);
+    }
+}
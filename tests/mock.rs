@@ -4,6 +4,10 @@ pub trait Trait {
 
     #[obce(id = 456)]
     fn another_method(&mut self, val: u32) -> u32;
+
+    fn helper_method(&mut self, val: u32) -> u32 {
+        self.method(val, val) + self.another_method(val)
+    }
 }
 
 #[obce::ink_lang::extension]
@@ -36,6 +40,11 @@ mod simple_contract {
         pub fn call_another_method(&mut self, val: u32) -> u32 {
             TestExtension.another_method(val)
         }
+
+        #[ink(message)]
+        pub fn call_helper_method(&mut self, val: u32) -> u32 {
+            TestExtension.helper_method(val)
+        }
     }
 }
 
@@ -49,6 +58,14 @@ mod simple_test {
 
     #[test]
     fn call_contract() {
+        TraitRegistration::register(());
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.call_method(100, 200), 300);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn legacy_registration() {
         register_chain_extensions(());
         let mut contract = crate::simple_contract::SimpleContract::new();
         assert_eq!(contract.call_method(100, 200), 300);
@@ -71,7 +88,7 @@ mod state_access {
 
     #[test]
     fn call_contract() {
-        register_chain_extensions(State::default());
+        TraitRegistration::register(State::default());
         let mut contract = crate::simple_contract::SimpleContract::new();
         assert_eq!(contract.call_method(100, 200), 1);
         assert_eq!(contract.call_method(100, 200), 2);
@@ -99,7 +116,7 @@ mod cross_method_state {
 
     #[test]
     fn call_contract() {
-        register_chain_extensions(State::default());
+        TraitRegistration::register(State::default());
         let mut contract = crate::simple_contract::SimpleContract::new();
         assert_eq!(contract.call_method(100, 200), 1);
         assert_eq!(contract.call_method(100, 200), 2);
@@ -121,12 +138,32 @@ mod internal_calls {
 
     #[test]
     fn call_contract() {
-        register_chain_extensions(());
+        TraitRegistration::register(());
         let mut contract = crate::simple_contract::SimpleContract::new();
         assert_eq!(contract.call_another_method(200), 400);
     }
 }
 
+mod helper_calls {
+    #[obce::mock]
+    impl crate::Trait for () {
+        fn method(&mut self, val: u32, another_val: u32) -> u32 {
+            val + another_val
+        }
+
+        fn another_method(&mut self, val: u32) -> u32 {
+            val * 10
+        }
+    }
+
+    #[test]
+    fn call_contract() {
+        TraitRegistration::register(());
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.call_helper_method(2), 24);
+    }
+}
+
 mod unregistered_call {
     #[obce::mock]
     impl crate::Trait for () {}
@@ -134,8 +171,122 @@ mod unregistered_call {
     #[test]
     #[should_panic(expected = "UnregisteredChainExtension")]
     fn call_contract() {
-        register_chain_extensions(());
+        TraitRegistration::register(());
         let mut contract = crate::simple_contract::SimpleContract::new();
         contract.call_another_method(200);
     }
 }
+
+mod call_recording {
+    use obce::ink_lang::mock::MockCall;
+
+    use crate::Trait;
+
+    #[derive(Default)]
+    pub struct State {
+        call_count: u32,
+    }
+
+    #[obce::mock]
+    impl crate::Trait for State {
+        fn method(&mut self, val: u32, another_val: u32) -> u32 {
+            self.call_count += 1;
+            val + another_val
+        }
+
+        fn another_method(&mut self, val: u32) -> u32 {
+            self.call_count += 1;
+            val
+        }
+    }
+
+    #[test]
+    fn call_contract() {
+        let handle = TraitRegistration::register(State::default());
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.call_method(100, 200), 300);
+        assert_eq!(contract.call_another_method(5), 5);
+        assert_eq!(contract.call_method(1, 2), 3);
+
+        assert_eq!(handle.context().call_count, 3);
+        assert_eq!(handle.calls().len(), 3);
+
+        assert_eq!(
+            handle.calls()[1],
+            MockCall {
+                method: "another_method",
+                func_id: obce::id!(Trait::another_method),
+                input: scale::Encode::encode(&5u32),
+            }
+        );
+
+        let args: Vec<(u32, u32)> = handle.calls_to("method").iter().map(MockCall::args).collect();
+        assert_eq!(args, vec![(100, 200), (1, 2)]);
+    }
+
+    #[test]
+    fn context_modification() {
+        let handle = TraitRegistration::register(State::default());
+        handle.context_mut().call_count = 10;
+
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        contract.call_another_method(5);
+
+        assert_eq!(handle.context().call_count, 11);
+    }
+}
+
+mod expectations {
+    use crate::MockTrait;
+
+    #[test]
+    fn call_contract() {
+        let mock = MockTrait::new();
+        mock.expect_method()
+            .with((100, 200))
+            .times(1)
+            .returning(|(val, another_val)| val + another_val);
+        mock.expect_another_method().times(2).returning(|val| val * 2);
+        mock.register();
+
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.call_method(100, 200), 300);
+        assert_eq!(contract.call_another_method(5), 10);
+        assert_eq!(contract.call_another_method(6), 12);
+    }
+
+    #[test]
+    fn matching_expectations() {
+        let mock = MockTrait::new();
+        mock.expect_another_method().with(1).returning(|_| 10);
+        mock.expect_another_method().withf(|val| *val > 1).returning(|_| 20);
+        mock.register();
+
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.call_another_method(1), 10);
+        assert_eq!(contract.call_another_method(2), 20);
+        assert_eq!(contract.call_another_method(3), 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected call to `method`")]
+    fn unexpected_call() {
+        let mock = MockTrait::new();
+        mock.expect_method().with((1, 2)).returning(|_| 3);
+        mock.register();
+
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        contract.call_method(2, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "`another_method` was expected to be called 2 times, but was called 1 times")]
+    fn call_count_verification() {
+        let mock = MockTrait::new();
+        mock.expect_another_method().times(2).returning(|val| val);
+        mock.register();
+
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        contract.call_another_method(1);
+    }
+}
@@ -0,0 +1,219 @@
+mod runtime;
+
+use obce::substrate::{
+    frame_support::dispatch::Weight,
+    frame_system::Config as SysConfig,
+    pallet_contracts::{
+        chain_extension::RetVal,
+        Config as ContractConfig,
+    },
+    sp_runtime::traits::StaticLookup,
+    testing::{
+        MockEnvironment,
+        DECODING_FAILED,
+        OUTPUT_BUFFER_TOO_SMALL,
+        OUT_OF_BOUNDS,
+        OUT_OF_GAS,
+        SENTINEL,
+    },
+    CallableChainExtension,
+    ChainExtensionEnvironment,
+    ExtensionContext,
+};
+use runtime::Runtime;
+use scale::Encode;
+
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn add(&mut self, a: u32, b: u32) -> u64;
+
+    fn process(&mut self, items: u32) -> u32;
+
+    fn store(&mut self, keys: Vec<u32>) -> u32;
+}
+
+#[obce::implementation]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    #[obce(weight(expr = "Weight::from_parts(100, 0)"))]
+    fn add(&mut self, a: u32, b: u32) -> u64 {
+        a as u64 + b as u64
+    }
+
+    #[obce(weight(
+        max = "Weight::from_parts(1_000 * items as u64, 0)",
+        actual = "Weight::from_parts(1_000 * *result as u64, 0)"
+    ))]
+    fn process(&mut self, items: u32) -> u32 {
+        items / 2
+    }
+
+    #[obce(weight(writes = "keys.len()", base = 1_000))]
+    fn store(&mut self, keys: Vec<u32>) -> u32 {
+        keys.len() as u32
+    }
+}
+
+const ADD: u32 = obce::method_hash!(ChainExtensionDefinition::add);
+const PROCESS: u32 = obce::method_hash!(ChainExtensionDefinition::process);
+const STORE: u32 = obce::method_hash!(ChainExtensionDefinition::store);
+
+fn raw_add_env(output_ptr: u32, output_len: u32) -> MockEnvironment<(), Runtime> {
+    MockEnvironment::new(())
+        .with_ids(
+            obce::id!(ChainExtensionDefinition),
+            obce::id!(ChainExtensionDefinition::add),
+        )
+        .with_input((1u32, 2u32).encode())
+        .with_output_ptr(output_ptr)
+        .with_output_len(output_len)
+}
+
+#[test]
+fn charged_weight_is_recorded() {
+    let mut env = MockEnvironment::<(), Runtime>::new(()).with_weight_limit(Weight::from_parts(1_000, 0));
+
+    let output = env.call::<dyn ChainExtensionDefinition, ADD, _>(&mut ChainExtension, (1, 2));
+
+    assert_eq!(output, Ok(3));
+    assert_eq!(env.output(), Some(&3u64.encode()[..]));
+    assert_eq!(env.charged_weights(), &[Weight::from_parts(100, 0)]);
+    assert_eq!(env.consumed_weight(), Weight::from_parts(100, 0));
+}
+
+#[test]
+fn call_state_is_reset_between_calls() {
+    let mut env = MockEnvironment::<(), Runtime>::new(()).with_weight_limit(Weight::from_parts(100_000, 0));
+
+    // `process` weight depends on the input, so it's read before charging.
+    let output = env.call::<dyn ChainExtensionDefinition, PROCESS, _>(&mut ChainExtension, 10);
+    assert_eq!(output, Ok(5));
+    assert!(env.read_before_charge());
+
+    let output = env.call::<dyn ChainExtensionDefinition, ADD, _>(&mut ChainExtension, (1, 2));
+    assert_eq!(output, Ok(3));
+    assert!(!env.read_before_charge());
+    assert_eq!(env.writes(), &[3u64.encode()]);
+    assert_eq!(env.charged_weights(), &[Weight::from_parts(100, 0)]);
+    assert!(env.adjusted_weights().is_empty());
+    assert_eq!(env.consumed_weight(), Weight::from_parts(100, 0));
+
+    // Weights charged by the previous call don't hide reads before charging.
+    let output = env.call::<dyn ChainExtensionDefinition, PROCESS, _>(&mut ChainExtension, 10);
+    assert_eq!(output, Ok(5));
+    assert!(env.read_before_charge());
+}
+
+#[test]
+fn weight_limit_is_enforced() {
+    let mut env = MockEnvironment::<(), Runtime>::new(()).with_weight_limit(Weight::from_parts(99, 0));
+
+    let output = env.call::<dyn ChainExtensionDefinition, ADD, _>(&mut ChainExtension, (1, 2));
+
+    assert_eq!(output, Err(OUT_OF_GAS));
+    assert!(env.charged_weights().is_empty());
+    assert!(env.writes().is_empty());
+}
+
+#[test]
+fn sentinel_output_is_skipped() {
+    let mut env = raw_add_env(SENTINEL, 0);
+
+    let result = <ChainExtension as CallableChainExtension<(), Runtime, _>>::call(&mut ChainExtension, &mut env);
+
+    assert!(matches!(result, Ok(RetVal::Converging(0))));
+    assert!(env.writes().is_empty());
+    assert_eq!(env.charged_weights(), &[Weight::from_parts(100, 0)]);
+}
+
+#[test]
+fn sentinel_output_is_rejected_if_skip_is_not_allowed() {
+    let mut env = raw_add_env(SENTINEL, 0);
+
+    assert_eq!((&mut env).write(&[1, 2, 3], false, None), Err(OUT_OF_BOUNDS));
+    assert!(env.writes().is_empty());
+}
+
+#[test]
+fn output_length_is_enforced() {
+    let mut env = raw_add_env(0, 4);
+
+    let result = <ChainExtension as CallableChainExtension<(), Runtime, _>>::call(&mut ChainExtension, &mut env);
+
+    assert!(matches!(result, Err(error) if error == OUTPUT_BUFFER_TOO_SMALL));
+    assert!(env.writes().is_empty());
+}
+
+#[test]
+fn output_length_is_updated_after_write() {
+    let mut env = raw_add_env(0, 8);
+
+    let result = <ChainExtension as CallableChainExtension<(), Runtime, _>>::call(&mut ChainExtension, &mut env);
+
+    assert!(matches!(result, Ok(RetVal::Converging(0))));
+    assert_eq!(env.writes(), &[3u64.encode()]);
+
+    // The output buffer length is shrunk to the written length, so the longer output doesn't fit.
+    assert_eq!((&mut env).write(&[0; 9], false, None), Err(OUTPUT_BUFFER_TOO_SMALL));
+}
+
+#[test]
+fn reads_outside_of_input_are_rejected() {
+    let mut env = MockEnvironment::<(), Runtime>::new(()).with_input(vec![1, 2, 3, 4]);
+
+    assert_eq!((&mut env).read_as_unbounded::<Vec<u8>>(5), Err(OUT_OF_BOUNDS));
+}
+
+#[test]
+fn input_is_decoded_entirely() {
+    // `None` doesn't consume the remaining bytes, so the input is rejected.
+    let mut env = MockEnvironment::<(), Runtime>::new(()).with_input(vec![0, 1, 0, 0, 0]);
+    assert_eq!((&mut env).read_as::<Option<u32>>(), Err(DECODING_FAILED));
+
+    let mut env = MockEnvironment::<(), Runtime>::new(()).with_input(vec![1, 1, 0, 0, 0]);
+    assert_eq!((&mut env).read_as::<Option<u32>>(), Ok(Some(1)));
+}
+
+#[test]
+fn weight_is_adjusted_to_actual() {
+    let mut env = MockEnvironment::<(), Runtime>::new(()).with_weight_limit(Weight::from_parts(100_000, 0));
+
+    let output = env.call::<dyn ChainExtensionDefinition, PROCESS, _>(&mut ChainExtension, 10);
+
+    assert_eq!(output, Ok(5));
+    assert_eq!(env.charged_weights(), &[Weight::from_parts(10_000, 0)]);
+    assert_eq!(
+        env.adjusted_weights(),
+        &[(Weight::from_parts(10_000, 0), Weight::from_parts(5_000, 0))]
+    );
+    assert_eq!(env.consumed_weight(), Weight::from_parts(5_000, 0));
+}
+
+#[test]
+fn weight_adjustment_never_exceeds_limit() {
+    let mut env = MockEnvironment::<(), Runtime>::new(()).with_weight_limit(Weight::from_parts(1_000, 0));
+
+    let charged = (&mut env).charge_weight(Weight::from_parts(400, 0)).unwrap();
+    (&mut env).adjust_weight(charged, Weight::zero());
+    (&mut env).adjust_weight(charged, Weight::zero());
+
+    assert_eq!(env.consumed_weight(), Weight::zero());
+    assert_eq!((&mut env).remaining_weight(), Weight::from_parts(1_000, 0));
+}
+
+#[test]
+fn integer_base_weight_is_charged_as_ref_time() {
+    // Test runtime uses zero `DbWeight`, so only the base weight is charged.
+    let mut env = MockEnvironment::<(), Runtime>::new(()).with_weight_limit(Weight::from_parts(1_000, 0));
+
+    let output = env.call::<dyn ChainExtensionDefinition, STORE, _>(&mut ChainExtension, vec![1, 2]);
+
+    assert_eq!(output, Ok(2));
+    assert_eq!(env.charged_weights(), &[Weight::from_parts(1_000, 0)]);
+}
@@ -0,0 +1,160 @@
+mod runtime;
+
+use obce::substrate::{
+    frame_system::Config as SysConfig,
+    pallet_contracts::{
+        Config as ContractConfig,
+        Origin,
+    },
+    sp_runtime::traits::{
+        StaticLookup,
+        UniqueSaturatedInto,
+    },
+    testing::{
+        MockEnvironment,
+        MockExt,
+    },
+    ChainExtensionEnvironment,
+    ContractExt,
+    ExtensionContext,
+};
+use runtime::{
+    Runtime,
+    ALICE,
+    BOB,
+    CONTRACT,
+};
+
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn refund_caller(&mut self, value: u32) -> bool;
+
+    fn block_number(&mut self) -> u64;
+
+    fn load(&mut self, key: Vec<u8>) -> Option<Vec<u8>>;
+
+    fn store(&mut self, key: Vec<u8>, value: Option<Vec<u8>>) -> bool;
+}
+
+#[obce::implementation]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+    E: ContractExt<T = T>,
+{
+    fn refund_caller(&mut self, value: u32) -> bool {
+        let ext = self.env.ext();
+
+        let Origin::Signed(caller) = ext.caller() else {
+            return false
+        };
+
+        ext.deposit_event(Vec::new(), value.to_le_bytes().to_vec());
+        ext.transfer(&caller, value.into()).is_ok()
+    }
+
+    fn block_number(&mut self) -> u64 {
+        self.env.ext().block_number().unique_saturated_into()
+    }
+
+    fn load(&mut self, key: Vec<u8>) -> Option<Vec<u8>> {
+        self.env.ext().get_storage(&key)
+    }
+
+    fn store(&mut self, key: Vec<u8>, value: Option<Vec<u8>>) -> bool {
+        self.env.ext().set_storage(&key, value).is_ok()
+    }
+}
+
+const REFUND_CALLER: u32 = obce::method_hash!(ChainExtensionDefinition::refund_caller);
+const BLOCK_NUMBER: u32 = obce::method_hash!(ChainExtensionDefinition::block_number);
+const LOAD: u32 = obce::method_hash!(ChainExtensionDefinition::load);
+const STORE: u32 = obce::method_hash!(ChainExtensionDefinition::store);
+
+#[test]
+fn transfers_and_events_are_recorded() {
+    let ext = MockExt::<Runtime>::new(ALICE, CONTRACT).with_balance(CONTRACT, 1000);
+    let mut env = MockEnvironment::<_, Runtime>::new(ext);
+
+    let output = env.call::<dyn ChainExtensionDefinition, REFUND_CALLER, _>(&mut ChainExtension, 100);
+
+    assert_eq!(output, Ok(true));
+    assert_eq!(env.ext().transfers(), &[(CONTRACT, ALICE, 100)]);
+    assert_eq!(env.ext().balance_of(&CONTRACT), 900);
+    assert_eq!(env.ext().balance_of(&ALICE), 100);
+    assert_eq!(env.ext().events(), &[(Vec::new(), 100u32.to_le_bytes().to_vec())]);
+}
+
+#[test]
+fn minimum_balance_is_kept() {
+    let ext = MockExt::<Runtime>::new(ALICE, CONTRACT)
+        .with_balance(CONTRACT, 1000)
+        .with_minimum_balance(950);
+    let mut env = MockEnvironment::<_, Runtime>::new(ext);
+
+    let output = env.call::<dyn ChainExtensionDefinition, REFUND_CALLER, _>(&mut ChainExtension, 100);
+
+    assert_eq!(output, Ok(false));
+    assert!(env.ext().transfers().is_empty());
+    assert_eq!(env.ext().balance_of(&CONTRACT), 1000);
+}
+
+#[test]
+fn root_caller_is_not_refunded() {
+    let ext = MockExt::<Runtime>::new(ALICE, CONTRACT)
+        .with_caller(Origin::Root)
+        .with_balance(CONTRACT, 1000);
+    let mut env = MockEnvironment::<_, Runtime>::new(ext);
+
+    let output = env.call::<dyn ChainExtensionDefinition, REFUND_CALLER, _>(&mut ChainExtension, 100);
+
+    assert_eq!(output, Ok(false));
+    assert!(env.ext().events().is_empty());
+    assert_eq!(env.ext().balance_of(&BOB), 0);
+}
+
+#[test]
+fn block_number_is_configurable() {
+    let ext = MockExt::<Runtime>::new(ALICE, CONTRACT).with_block_number(10);
+    let mut env = MockEnvironment::<_, Runtime>::new(ext);
+
+    let output = env.call::<dyn ChainExtensionDefinition, BLOCK_NUMBER, _>(&mut ChainExtension, ());
+
+    assert_eq!(output, Ok(10));
+}
+
+#[test]
+fn storage_is_read_and_written() {
+    let ext = MockExt::<Runtime>::new(ALICE, CONTRACT).with_storage(vec![1], vec![10]);
+    let mut env = MockEnvironment::<_, Runtime>::new(ext);
+
+    let output = env.call::<dyn ChainExtensionDefinition, LOAD, _>(&mut ChainExtension, vec![1]);
+    assert_eq!(output, Ok(Some(vec![10])));
+
+    let output = env.call::<dyn ChainExtensionDefinition, STORE, _>(&mut ChainExtension, (vec![2], Some(vec![20])));
+    assert_eq!(output, Ok(true));
+
+    let output = env.call::<dyn ChainExtensionDefinition, STORE, _>(&mut ChainExtension, (vec![1], None));
+    assert_eq!(output, Ok(true));
+
+    let output = env.call::<dyn ChainExtensionDefinition, LOAD, _>(&mut ChainExtension, vec![1]);
+    assert_eq!(output, Ok(None));
+
+    assert_eq!(env.ext().storage().get(&[2][..]), Some(&vec![20]));
+    assert_eq!(env.ext().storage().len(), 1);
+}
+
+#[test]
+fn long_storage_keys_are_rejected() {
+    let ext = MockExt::<Runtime>::new(ALICE, CONTRACT);
+    let mut env = MockEnvironment::<_, Runtime>::new(ext);
+
+    let output = env.call::<dyn ChainExtensionDefinition, STORE, _>(&mut ChainExtension, (vec![0; 129], Some(vec![1])));
+
+    assert_eq!(output, Ok(false));
+    assert!(env.ext().storage().is_empty());
+}
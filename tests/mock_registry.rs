@@ -0,0 +1,223 @@
+#[obce::definition(id = 123)]
+pub trait Balances {
+    fn balance_of(&mut self, account: u32) -> u128;
+
+    fn total_issuance(&mut self) -> u128;
+}
+
+#[obce::definition(id = 456)]
+pub trait Assets {
+    fn asset_balance_of(&mut self, asset: u32, account: u32) -> u128;
+}
+
+#[obce::definition(id = 123)]
+pub trait ConflictingBalances {
+    fn balance_of(&mut self, account: u32) -> u128;
+}
+
+#[obce::definition(id = 123)]
+pub trait ConflictingIssuance {
+    fn issuance(&mut self) -> u128;
+}
+
+#[obce::ink_lang::extension]
+struct TestExtension;
+
+impl Balances for TestExtension {}
+
+impl Assets for TestExtension {}
+
+#[ink::contract]
+mod simple_contract {
+    use crate::{
+        Assets,
+        Balances,
+        TestExtension,
+    };
+    use obce::ink_lang::CallError;
+
+    #[ink(storage)]
+    pub struct SimpleContract {}
+
+    impl SimpleContract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            SimpleContract {}
+        }
+
+        #[ink(message)]
+        pub fn balance_of(&mut self, account: u32) -> u128 {
+            TestExtension.balance_of(account)
+        }
+
+        #[ink(message)]
+        pub fn total_issuance(&mut self) -> u128 {
+            TestExtension.total_issuance()
+        }
+
+        #[ink(message)]
+        pub fn supported_balances_methods(&self) -> Result<Vec<u16>, CallError> {
+            obce::ink_lang::supported_methods::<dyn Balances>()
+        }
+
+        #[ink(message)]
+        pub fn asset_balance_of(&mut self, asset: u32, account: u32) -> u128 {
+            TestExtension.asset_balance_of(asset, account)
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct State {
+    balance: u128,
+}
+
+#[obce::mock(registry_only)]
+impl crate::Balances for State {
+    fn balance_of(&mut self, account: u32) -> u128 {
+        self.balance + account as u128
+    }
+}
+
+#[obce::mock(registration = "EmptyBalances")]
+impl crate::Balances for () {
+    fn balance_of(&mut self, _: u32) -> u128 {
+        0
+    }
+}
+
+#[obce::mock(registration = "IssuanceRegistration")]
+impl crate::Balances for u128 {
+    fn total_issuance(&mut self) -> u128 {
+        *self
+    }
+}
+
+#[obce::mock(registry_only)]
+impl crate::Assets for () {
+    fn asset_balance_of(&mut self, asset: u32, account: u32) -> u128 {
+        (asset * account) as u128
+    }
+}
+
+#[obce::mock(registry_only)]
+impl crate::ConflictingBalances for () {
+    fn balance_of(&mut self, _: u32) -> u128 {
+        0
+    }
+}
+
+#[test]
+fn multiple_extensions() {
+    let _registry = obce::ink_lang::mock::MockRegistry::new()
+        .with::<BalancesRegistration>(State { balance: 100 })
+        .with::<AssetsRegistration>(())
+        .install();
+
+    let mut contract = simple_contract::SimpleContract::new();
+    assert_eq!(contract.balance_of(1), 101);
+    assert_eq!(contract.asset_balance_of(2, 3), 6);
+}
+
+#[test]
+fn handle_access() {
+    let registry = obce::ink_lang::mock::MockRegistry::new()
+        .with::<BalancesRegistration>(State::default())
+        .install();
+
+    let handle = registry.handle::<BalancesRegistration>().unwrap();
+    handle.context_mut().balance = 10;
+
+    let mut contract = simple_contract::SimpleContract::new();
+    assert_eq!(contract.balance_of(1), 11);
+    assert_eq!(handle.calls_to("balance_of").len(), 1);
+
+    assert!(registry.handle::<AssetsRegistration>().is_none());
+}
+
+#[test]
+fn reset_between_phases() {
+    let mut registry = obce::ink_lang::mock::MockRegistry::new()
+        .with::<BalancesRegistration>(State { balance: 100 })
+        .install();
+
+    let mut contract = simple_contract::SimpleContract::new();
+    assert_eq!(contract.balance_of(1), 101);
+
+    registry.reset();
+    assert!(registry.handle::<BalancesRegistration>().is_none());
+
+    let _registry = registry.with::<BalancesRegistration>(State { balance: 200 }).install();
+    assert_eq!(contract.balance_of(1), 201);
+}
+
+#[test]
+#[should_panic(expected = "was unregistered by `MockRegistry::reset`")]
+fn call_after_reset() {
+    let mut registry = obce::ink_lang::mock::MockRegistry::new()
+        .with::<BalancesRegistration>(State::default())
+        .install();
+
+    registry.reset();
+
+    let mut contract = simple_contract::SimpleContract::new();
+    contract.balance_of(1);
+}
+
+#[test]
+#[should_panic(expected = "conflicting chain extension function identifier")]
+fn conflicting_ids() {
+    let _ = obce::ink_lang::mock::MockRegistry::new()
+        .with::<BalancesRegistration>(State::default())
+        .with::<ConflictingBalancesRegistration>(());
+}
+
+#[obce::mock(registry_only)]
+impl crate::ConflictingIssuance for () {
+    fn issuance(&mut self) -> u128 {
+        0
+    }
+}
+
+#[test]
+fn partial_mocks() {
+    let _registry = obce::ink_lang::mock::MockRegistry::new()
+        .with::<BalancesRegistration>(State { balance: 100 })
+        .with::<IssuanceRegistration>(1000)
+        .install();
+
+    let mut contract = simple_contract::SimpleContract::new();
+    assert_eq!(contract.balance_of(1), 101);
+    assert_eq!(contract.total_issuance(), 1000);
+
+    // Capability handshake is shared by the mocks of the same chain extension.
+    let mut supported_methods = contract.supported_balances_methods().unwrap();
+    supported_methods.sort();
+
+    let mut expected_methods = vec![obce::id!(Balances::balance_of), obce::id!(Balances::total_issuance)];
+    expected_methods.sort();
+
+    assert_eq!(supported_methods, expected_methods);
+}
+
+#[test]
+#[should_panic(expected = "conflicting chain extension function identifier")]
+fn conflicting_handshakes() {
+    let _ = obce::ink_lang::mock::MockRegistry::new()
+        .with::<BalancesRegistration>(State::default())
+        .with::<ConflictingIssuanceRegistration>(());
+}
+
+#[test]
+fn registration_swap() {
+    let handle = BalancesRegistration::register(State { balance: 100 });
+
+    let mut contract = simple_contract::SimpleContract::new();
+    assert_eq!(contract.balance_of(1), 101);
+    assert_eq!(handle.calls_to("balance_of").len(), 1);
+
+    // Mocks with the same function identifiers replace each other.
+    let handle = EmptyBalances::register(());
+    assert_eq!(contract.balance_of(1), 0);
+    assert_eq!(handle.calls_to("balance_of").len(), 1);
+}
@@ -0,0 +1 @@
+mod roundtrip_definition;
@@ -0,0 +1,14 @@
+//! Chain extension definition with round-trip tests, that is shared by ink! and Substrate tests.
+
+#[obce::definition(proptest)]
+pub trait Trait {
+    fn no_input(&self) -> u32;
+
+    fn single_input(&self, val: u32) -> Option<u64>;
+
+    fn tuple_input(&self, val: (u8, u16));
+
+    fn multiple_inputs(&mut self, one: u8, two: Vec<u32>, three: bool) -> (u128, [u8; 4]);
+
+    fn result_output(&self, account: [u8; 32]) -> Result<u32, u8>;
+}
@@ -0,0 +1,138 @@
+//! Round-trips through the Substrate dispatcher, with inputs encoded the same way ink! glue code does:
+//! as a single value for single-argument methods, and as a tuple of arguments otherwise.
+
+mod roundtrip_definition;
+mod runtime;
+
+use obce::substrate::{
+    frame_system::Config as SysConfig,
+    pallet_contracts::{
+        chain_extension::RetVal,
+        Config as ContractConfig,
+    },
+    sp_runtime::traits::StaticLookup,
+    testing::{
+        MockEnvironment,
+        DECODING_FAILED,
+    },
+    CallableChainExtension,
+    ChainExtensionEnvironment,
+    CriticalError,
+    ExtensionContext,
+};
+use scale::{
+    Decode,
+    Encode,
+};
+
+use roundtrip_definition::Trait;
+use runtime::Runtime;
+
+pub struct Extension;
+
+#[obce::implementation]
+impl<'a, E, T, Env> Trait for ExtensionContext<'a, E, T, Env, Extension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    fn no_input(&self) -> u32 {
+        42
+    }
+
+    fn single_input(&self, val: u32) -> Option<u64> {
+        (val != 0).then_some(val as u64 * 2)
+    }
+
+    fn tuple_input(&self, val: (u8, u16)) {
+        assert_eq!(val, (1, 2));
+    }
+
+    fn multiple_inputs(&mut self, one: u8, two: Vec<u32>, three: bool) -> (u128, [u8; 4]) {
+        (two.iter().sum::<u32>() as u128 + three as u128, [one; 4])
+    }
+
+    fn result_output(&self, account: [u8; 32]) -> Result<u32, u8> {
+        match account[0] {
+            0 => Err(account[1]),
+            first => Ok(first as u32),
+        }
+    }
+}
+
+/// Call the method using raw input bytes, returning the raw output.
+fn call_raw(func_id: u16, input: Vec<u8>) -> Result<Vec<u8>, CriticalError> {
+    let mut env = MockEnvironment::<(), Runtime>::new(())
+        .with_ids(obce::id!(Trait), func_id)
+        .with_input(input);
+
+    match <Extension as CallableChainExtension<(), Runtime, _>>::call(&mut Extension, &mut env)? {
+        RetVal::Converging(0) => Ok(env.output().unwrap_or_default().to_vec()),
+        _ => panic!("chain extension returned a non-zero status code"),
+    }
+}
+
+fn call<I: Encode, O: Decode>(func_id: u16, input: I) -> Result<O, CriticalError> {
+    call_raw(func_id, input.encode())
+        .map(|output| O::decode(&mut &output[..]).expect("unable to decode chain extension output"))
+}
+
+#[test]
+fn no_input() {
+    assert_eq!(call::<_, u32>(obce::id!(Trait::no_input), ()), Ok(42));
+}
+
+#[test]
+fn single_input() {
+    let id = obce::id!(Trait::single_input);
+
+    assert_eq!(call::<_, Option<u64>>(id, 21u32), Ok(Some(42)));
+    assert_eq!(call::<_, Option<u64>>(id, 0u32), Ok(None));
+}
+
+#[test]
+fn tuple_input() {
+    assert_eq!(call::<_, ()>(obce::id!(Trait::tuple_input), (1u8, 2u16)), Ok(()));
+}
+
+#[test]
+fn multiple_inputs() {
+    assert_eq!(
+        call::<_, (u128, [u8; 4])>(obce::id!(Trait::multiple_inputs), (7u8, vec![1u32, 2, 3], true)),
+        Ok((7, [7; 4]))
+    );
+}
+
+#[test]
+fn result_output() {
+    let id = obce::id!(Trait::result_output);
+
+    let mut account = [0; 32];
+    account[1] = 5;
+    assert_eq!(call::<_, Result<u32, u8>>(id, account), Ok(Err(5)));
+
+    account[0] = 3;
+    assert_eq!(call::<_, Result<u32, u8>>(id, account), Ok(Ok(3)));
+}
+
+#[test]
+fn inputs_must_match_argument_encoding() {
+    // Only the first argument of a multiple-argument method.
+    assert_eq!(
+        call_raw(obce::id!(Trait::multiple_inputs), 7u8.encode()),
+        Err(DECODING_FAILED)
+    );
+
+    // Arguments of a multiple-argument method, that are followed by another value.
+    assert_eq!(
+        call_raw(obce::id!(Trait::multiple_inputs), (7u8, vec![1u32], true, 0u8).encode()),
+        Err(DECODING_FAILED)
+    );
+
+    // Tuple argument, that is missing its last element.
+    assert_eq!(
+        call_raw(obce::id!(Trait::tuple_input), 1u8.encode()),
+        Err(DECODING_FAILED)
+    );
+}
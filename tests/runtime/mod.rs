@@ -0,0 +1,143 @@
+//! Minimal runtime that is used to test Substrate chain extensions
+//! with `MockEnvironment` and `MockExt`.
+
+#![allow(dead_code)]
+
+use frame_support::{
+    parameter_types,
+    traits::{
+        ConstBool,
+        ConstU32,
+        ConstU64,
+        Everything,
+        Nothing,
+        Randomness,
+        Time,
+    },
+};
+use pallet_contracts::{
+    DefaultAddressGenerator,
+    Frame,
+    Schedule,
+};
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{
+        BlakeTwo256,
+        IdentityLookup,
+    },
+    AccountId32,
+};
+
+pub type AccountId = AccountId32;
+pub type Balance = u64;
+
+pub const ALICE: AccountId = AccountId32::new([1; 32]);
+pub const BOB: AccountId = AccountId32::new([2; 32]);
+pub const CONTRACT: AccountId = AccountId32::new([3; 32]);
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime!(
+    pub enum Runtime where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Contracts: pallet_contracts::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+impl frame_system::Config for Runtime {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<AccountId>;
+    type Header = Header;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Runtime {
+    type Balance = Balance;
+    type DustRemoval = ();
+    type RuntimeEvent = RuntimeEvent;
+    type ExistentialDeposit = ConstU64<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type FreezeIdentifier = ();
+    type MaxFreezes = ();
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type MaxHolds = ();
+}
+
+/// Deterministic randomness source, since chain extension tests don't need a real one.
+pub struct TestRandomness;
+
+impl Randomness<H256, u64> for TestRandomness {
+    fn random(_subject: &[u8]) -> (H256, u64) {
+        (H256::zero(), 0)
+    }
+}
+
+/// Time source that always returns zero, since `MockExt` tracks the current timestamp by itself.
+pub struct TestTime;
+
+impl Time for TestTime {
+    type Moment = u64;
+
+    fn now() -> Self::Moment {
+        0
+    }
+}
+
+parameter_types! {
+    pub ContractsSchedule: Schedule<Runtime> = Default::default();
+}
+
+impl pallet_contracts::Config for Runtime {
+    type Time = TestTime;
+    type Randomness = TestRandomness;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type CallFilter = Nothing;
+    type CallStack = [Frame<Self>; 5];
+    type WeightPrice = ();
+    type WeightInfo = ();
+    type ChainExtension = ();
+    type Schedule = ContractsSchedule;
+    type DepositPerByte = ConstU64<1>;
+    type DepositPerItem = ConstU64<1>;
+    type DefaultDepositLimit = ConstU64<{ u64::MAX }>;
+    type AddressGenerator = DefaultAddressGenerator;
+    type MaxCodeLen = ConstU32<{ 123 * 1024 }>;
+    type MaxStorageKeyLen = ConstU32<128>;
+    type UnsafeUnstableInterface = ConstBool<false>;
+    type MaxDebugBufferLen = ConstU32<{ 2 * 1024 * 1024 }>;
+    type Migrations = ();
+}
@@ -0,0 +1,231 @@
+use obce::ink_lang::env::test::ChainExtension;
+
+#[obce::error]
+pub enum Error {
+    #[obce(ret_val = "100")]
+    First,
+}
+
+#[obce::error]
+pub enum CustomError {
+    #[obce(ret_val = "100")]
+    First,
+
+    #[obce(unknown_status)]
+    Other(u32),
+}
+
+#[obce::definition(id = 123)]
+pub trait Trait {
+    fn method(&mut self, val: u32) -> u32;
+
+    #[obce(ret_val)]
+    fn ret_val_method(&mut self) -> Result<u32, Error>;
+
+    #[obce(ret_val)]
+    fn custom_ret_val_method(&mut self) -> Result<u32, CustomError>;
+}
+
+#[obce::ink_lang::extension]
+struct TestExtension;
+
+impl Trait for TestExtension {}
+
+#[ink::contract]
+mod simple_contract {
+    use obce::ink_lang::CallError;
+
+    use crate::{
+        CustomError,
+        Error,
+        TestExtension,
+        Trait,
+    };
+
+    #[ink(storage)]
+    pub struct SimpleContract {}
+
+    impl SimpleContract {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            SimpleContract {}
+        }
+
+        #[ink(message)]
+        pub fn try_call_method(&mut self, val: u32) -> Result<u32, CallError> {
+            TestExtension.try_method(val)
+        }
+
+        #[ink(message)]
+        pub fn try_call_ret_val_method(&mut self) -> Result<Result<u32, Error>, CallError> {
+            TestExtension.try_ret_val_method()
+        }
+
+        #[ink(message)]
+        pub fn try_call_custom_ret_val_method(&mut self) -> Result<Result<u32, CustomError>, CallError> {
+            TestExtension.try_custom_ret_val_method()
+        }
+    }
+}
+
+/// Raw chain extension that returns a predefined status code and output.
+struct RawExtension {
+    func_id: u32,
+    status_code: u32,
+    output: Vec<u8>,
+}
+
+impl RawExtension {
+    fn register(method_id: u16, status_code: u32, output: Vec<u8>) {
+        obce::ink_lang::env::test::register_chain_extension(RawExtension {
+            func_id: (obce::id!(Trait) as u32) << 16 | (method_id as u32),
+            status_code,
+            output,
+        });
+    }
+}
+
+impl ChainExtension for RawExtension {
+    fn func_id(&self) -> u32 {
+        self.func_id
+    }
+
+    fn call(&mut self, _: &[u8], output: &mut Vec<u8>) -> u32 {
+        output.extend_from_slice(&self.output);
+        self.status_code
+    }
+}
+
+mod successful_call {
+    #[obce::mock]
+    impl crate::Trait for () {
+        fn method(&mut self, val: u32) -> u32 {
+            val * 2
+        }
+
+        fn ret_val_method(&mut self) -> Result<u32, crate::Error> {
+            Err(crate::Error::First)
+        }
+    }
+
+    #[test]
+    fn call_contract() {
+        TraitRegistration::register(());
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.try_call_method(100), Ok(200));
+        assert_eq!(contract.try_call_ret_val_method(), Ok(Err(crate::Error::First)));
+    }
+}
+
+mod mocked_status_code {
+    use obce::ink_lang::{
+        CallError,
+        MockResult,
+    };
+
+    #[obce::mock]
+    impl crate::Trait for () {
+        fn method(&mut self, _: u32) -> MockResult<u32> {
+            MockResult::Status(200)
+        }
+
+        fn ret_val_method(&mut self) -> MockResult<Result<u32, crate::Error>> {
+            MockResult::Status(100)
+        }
+    }
+
+    #[test]
+    fn call_contract() {
+        TraitRegistration::register(());
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.try_call_method(100), Err(CallError::Status(200)));
+        assert_eq!(contract.try_call_ret_val_method(), Ok(Err(crate::Error::First)));
+    }
+}
+
+mod mocked_trap {
+    use obce::ink_lang::MockResult;
+
+    #[obce::mock]
+    impl crate::Trait for () {
+        fn method(&mut self, _: u32) -> MockResult<u32> {
+            MockResult::Trap("insufficient balance")
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "chain extension call trapped: insufficient balance")]
+    fn call_contract() {
+        TraitRegistration::register(());
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        let _ = contract.try_call_method(100);
+    }
+}
+
+mod unknown_function {
+    use obce::{
+        codegen::UNKNOWN_FUNCTION_STATUS_CODE,
+        ink_lang::CallError,
+    };
+
+    use crate::{
+        RawExtension,
+        Trait,
+    };
+
+    #[test]
+    fn call_contract() {
+        RawExtension::register(obce::id!(Trait::method), UNKNOWN_FUNCTION_STATUS_CODE, vec![]);
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.try_call_method(100), Err(CallError::UnknownFunction));
+    }
+}
+
+mod decode_failure {
+    use obce::ink_lang::CallError;
+
+    use crate::{
+        RawExtension,
+        Trait,
+    };
+
+    #[test]
+    fn call_contract() {
+        RawExtension::register(obce::id!(Trait::method), 0, vec![1]);
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.try_call_method(100), Err(CallError::Decode));
+    }
+}
+
+mod status_codes {
+    use obce::ink_lang::CallError;
+
+    use crate::{
+        RawExtension,
+        Trait,
+    };
+
+    #[test]
+    fn known_status_code() {
+        RawExtension::register(obce::id!(Trait::ret_val_method), 100, vec![]);
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.try_call_ret_val_method(), Ok(Err(crate::Error::First)));
+    }
+
+    #[test]
+    fn unknown_status_code() {
+        RawExtension::register(obce::id!(Trait::ret_val_method), 200, vec![]);
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(contract.try_call_ret_val_method(), Err(CallError::Status(200)));
+    }
+
+    #[test]
+    fn unknown_status_variant() {
+        RawExtension::register(obce::id!(Trait::custom_ret_val_method), 200, vec![]);
+        let mut contract = crate::simple_contract::SimpleContract::new();
+        assert_eq!(
+            contract.try_call_custom_ret_val_method(),
+            Ok(Err(crate::CustomError::Other(200)))
+        );
+    }
+}
@@ -0,0 +1,7 @@
+use trybuild::TestCases;
+
+#[test]
+fn ui() {
+    let cases = TestCases::new();
+    cases.pass("tests/ui/benchmarks/pass_*.rs");
+}
@@ -0,0 +1,91 @@
+use obce::substrate::{
+    frame_benchmarking::Benchmarking,
+    frame_support::dispatch::Weight,
+    frame_system::Config as SysConfig,
+    pallet_contracts::Config as ContractConfig,
+    sp_runtime::traits::StaticLookup,
+    sp_std::vec::Vec,
+    ChainExtensionEnvironment,
+    ExtensionContext
+};
+
+#[derive(Default)]
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn empty_method(&mut self);
+
+    fn sum(&mut self, items: Vec<u32>) -> u64;
+}
+
+pub struct ExtensionWeights;
+
+impl ChainExtensionDefinitionWeightInfo for ExtensionWeights {
+    fn empty_method() -> Weight {
+        Weight::from_parts(1_000, 0)
+    }
+
+    fn sum(n: u32) -> Weight {
+        Weight::from_parts(1_000 + 100 * n as u64, 0)
+    }
+}
+
+#[obce::implementation(benchmarks, weight_info = "ExtensionWeights")]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    #[obce(weight(info))]
+    fn empty_method(&mut self) {}
+
+    #[obce(
+        weight(info = "items.len() as u32"),
+        benchmark(
+            components(n = "0 .. 1000"),
+            setup = "let items: Vec<u32> = (0..n).collect();",
+            args = "items"
+        )
+    )]
+    fn sum(&mut self, items: Vec<u32>) -> u64 {
+        items.into_iter().map(u64::from).sum()
+    }
+}
+
+#[derive(Default)]
+pub struct AnotherChainExtension;
+
+#[obce::definition]
+pub trait AnotherChainExtensionDefinition {
+    fn double(&mut self, value: u32) -> u64;
+}
+
+// Benchmarks of multiple chain extensions can be defined in the same module.
+#[obce::implementation(benchmarks(ext = "()"))]
+impl<'a, E, T, Env> AnotherChainExtensionDefinition for ExtensionContext<'a, E, T, Env, AnotherChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    #[obce(benchmark(args = "42u32"))]
+    fn double(&mut self, value: u32) -> u64 {
+        value as u64 * 2
+    }
+}
+
+#[allow(dead_code)]
+fn assert_benchmarks<T>()
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+{
+    fn is_benchmarking<B: Benchmarking>() {}
+
+    is_benchmarking::<ChainExtensionDefinitionBenchmarks<T>>();
+    is_benchmarking::<AnotherChainExtensionDefinitionBenchmarks<T>>();
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+#[obce::definition]
+pub trait Trait {
+    #[obce(deprecated = "use `method` instead")]
+    fn old_method(&self, val: u32) -> u32;
+
+    fn method(&self, val: u32) -> u32;
+}
+
+#[obce::ink_lang::extension]
+struct TestExtension;
+
+impl Trait for TestExtension {}
+
+#[deny(deprecated)]
+fn main() {
+    TestExtension.old_method(123);
+}
@@ -0,0 +1,8 @@
+#[obce::definition(proptest)]
+pub trait Trait {
+    type Balance;
+
+    fn balance_of(&self, account: [u8; 32]) -> Self::Balance;
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#[obce::error]
+enum Error {
+    #[obce(ret_val = "4294967295")]
+    One,
+}
+
+#[obce::error]
+enum AnotherError {
+    #[obce(ret_val = "u32::MAX")]
+    One,
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+#[obce::error]
+enum Error {
+    #[obce(unknown_status)]
+    One,
+}
+
+#[obce::error]
+enum AnotherError {
+    #[obce(unknown_status)]
+    One(u64),
+}
+
+#[obce::error]
+enum NamedError {
+    #[obce(unknown_status)]
+    One { status_code: u32 },
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[obce::error]
+enum Error {
+    #[obce(ret_val = "100", unknown_status)]
+    One(u32),
+}
+
+fn main() {}
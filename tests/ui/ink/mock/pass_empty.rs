@@ -5,5 +5,5 @@ pub trait Trait {}
 impl Trait for () {}
 
 fn main() {
-    register_chain_extensions(());
+    TraitRegistration::register(());
 }
@@ -9,5 +9,5 @@ impl Trait for () {
 }
 
 fn main() {
-    register_chain_extensions(());
+    TraitRegistration::register(());
 }
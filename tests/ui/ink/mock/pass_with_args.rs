@@ -11,5 +11,5 @@ impl Trait for () {
 }
 
 fn main() {
-    register_chain_extensions(());
+    TraitRegistration::register(());
 }
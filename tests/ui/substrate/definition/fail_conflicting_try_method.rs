@@ -1,8 +1,8 @@
 #[obce::definition]
 pub trait Trait {
-    fn default_method() -> u32 {
-        123
-    }
+    fn method(&self);
+
+    fn try_method(&self);
 }
 
 fn main() {}
@@ -0,0 +1,6 @@
+#[obce::definition]
+pub trait Trait {
+    const MAX_BATCH: u32;
+}
+
+fn main() {}
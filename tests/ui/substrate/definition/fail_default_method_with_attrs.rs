@@ -0,0 +1,11 @@
+#[obce::definition]
+pub trait Trait {
+    fn method(&self, val: u32) -> u32;
+
+    #[obce(id = 123)]
+    fn helper(&self) -> u32 {
+        self.method(1)
+    }
+}
+
+fn main() {}
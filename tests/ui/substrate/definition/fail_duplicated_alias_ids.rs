@@ -0,0 +1,9 @@
+#[obce::definition]
+pub trait Trait {
+    #[obce(id = 1, alias_id = 2)]
+    fn extension_method(&self);
+    #[obce(id = 2)]
+    fn another_extension_method(&self);
+}
+
+fn main() {}
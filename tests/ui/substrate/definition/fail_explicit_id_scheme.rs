@@ -0,0 +1,6 @@
+#[obce::definition(id = 123, id_scheme = "explicit")]
+pub trait Trait {
+    fn extension_method(&self);
+}
+
+fn main() {}
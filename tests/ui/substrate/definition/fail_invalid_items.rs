@@ -1,6 +1,6 @@
 #[obce::definition]
 pub trait Trait {
-    const INVALID_ITEM: u32 = 123;
+    invalid_item!();
 }
 
 fn main() {}
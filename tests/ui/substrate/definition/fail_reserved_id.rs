@@ -0,0 +1,7 @@
+#[obce::definition]
+pub trait Trait {
+    #[obce(id = 0xFFFF)]
+    fn extension_method(&self);
+}
+
+fn main() {}
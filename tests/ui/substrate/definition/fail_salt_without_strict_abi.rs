@@ -0,0 +1,7 @@
+#[obce::definition]
+pub trait Trait {
+    #[obce(salt = 1)]
+    fn extension_method(&self, val: u32) -> u64;
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[obce::definition(version = 2)]
+pub trait Trait {
+    #[obce(since = 3)]
+    fn extension_method(&self);
+}
+
+fn main() {}
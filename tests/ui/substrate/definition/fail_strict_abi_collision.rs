@@ -0,0 +1,9 @@
+// Only the lower two bytes of signature hashes are folded into method identifiers,
+// so methods with different names and signatures may end up with the same identifier.
+#[obce::definition(strict_abi)]
+pub trait Trait {
+    fn first_method(&self, val: u32);
+    fn second_method(&self, val: [u8; 85768]);
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#[obce::definition(strict_abi)]
+pub trait Trait {
+    #[obce(id = 1)]
+    fn extension_method(&self, val: u32) -> u64;
+}
+
+#[obce::definition(id_scheme = "explicit", id = 1, strict_abi)]
+pub trait ExplicitTrait {
+    #[obce(id = 1)]
+    fn extension_method(&self, val: u32) -> u64;
+}
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[obce::definition(id_scheme = "random")]
+pub trait Trait {}
+
+fn main() {}
@@ -0,0 +1,22 @@
+#[obce::definition]
+pub trait Trait {
+    type Balance;
+
+    const MAX_BATCH: u32 = 16;
+
+    fn balance(&self, account: u32) -> Self::Balance;
+
+    fn transfer(&mut self, to: u32, amount: Self::Balance) -> Option<Self::Balance>;
+
+    fn decimals(&self) -> u8;
+}
+
+// Methods that don't use associated types are still available on trait objects.
+fn decimals(extension: &dyn Trait) -> u8 {
+    extension.decimals()
+}
+
+fn main() {
+    assert_eq!(<dyn Trait>::MAX_BATCH, 16);
+    let _ = decimals;
+}
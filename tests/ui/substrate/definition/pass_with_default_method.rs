@@ -0,0 +1,10 @@
+#[obce::definition]
+pub trait Trait {
+    fn method(&self, val: u32) -> u32;
+
+    fn helper(&self) -> u32 {
+        self.method(1) + self.method(2)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+mod const_eq;
+
+use const_eq::*;
+
+use obce::id;
+
+#[obce::definition(id = 123, id_scheme = "explicit")]
+pub trait Trait {
+    #[obce(id = 1)]
+    fn extension_method(&self);
+
+    fn helper_method(&self) {
+        self.extension_method()
+    }
+}
+
+fn main() {
+    assert_const_eq::<{ id!(Trait) }, 123>();
+    assert_const_eq::<{ id!(Trait::extension_method) }, 1>();
+}
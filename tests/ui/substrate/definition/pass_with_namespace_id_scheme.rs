@@ -0,0 +1,22 @@
+mod const_eq;
+
+use const_eq::*;
+
+use obce::{
+    codegen::{
+        ExtensionDescription,
+        IdScheme,
+    },
+    id,
+};
+
+#[obce::definition(id_scheme = "namespace", namespace = "my_pallet")]
+pub trait Trait {
+    fn extension_method(&self);
+}
+
+fn main() {
+    assert_const_eq::<{ id!(Trait) }, 0xa0e6>();
+    assert_const_eq::<{ id!(Trait::extension_method) }, 0x6e3e>();
+    assert_eq!(<dyn Trait as ExtensionDescription>::ID_SCHEME, IdScheme::Namespace("my_pallet"));
+}
@@ -0,0 +1,44 @@
+use obce::codegen::{
+    ExtensionDescription,
+    IdScheme,
+    MethodDescription,
+};
+
+#[obce::definition(id_scheme = "path")]
+pub trait Trait {
+    fn extension_method(&self);
+}
+
+#[obce::definition(id_scheme = "path")]
+pub trait AnotherTrait {
+    fn extension_method(&self);
+}
+
+mod name {
+    #[obce::definition]
+    pub trait Trait {
+        fn extension_method(&self);
+    }
+}
+
+fn main() {
+    // Trait paths are qualified with the name of the crate that contains the definition.
+    assert_eq!(
+        <dyn Trait as ExtensionDescription>::ID_SCHEME,
+        IdScheme::Path(env!("CARGO_CRATE_NAME"))
+    );
+    assert_ne!(
+        <dyn Trait as ExtensionDescription>::ID,
+        <dyn name::Trait as ExtensionDescription>::ID
+    );
+
+    // Methods with the same name in different traits have different identifiers.
+    assert_ne!(
+        obce::id!(Trait::extension_method),
+        obce::id!(AnotherTrait::extension_method)
+    );
+    assert_ne!(
+        obce::id!(Trait::extension_method),
+        <dyn name::Trait as MethodDescription<{ obce::method_hash!(Trait::extension_method) }>>::ID
+    );
+}
@@ -0,0 +1,103 @@
+mod const_eq;
+
+use const_eq::*;
+
+use obce::{
+    codegen::{
+        ExtensionDescription,
+        MethodDescription,
+    },
+    id,
+};
+
+#[obce::definition(strict_abi)]
+pub trait Trait {
+    fn extension_method(&self, val: u32, bytes: Vec<u8>) -> Option<u64>;
+}
+
+#[obce::definition(strict_abi)]
+pub trait RespelledTrait {
+    fn extension_method(&self, val: ::core::primitive::u32, bytes: std::vec::Vec<u8>) -> core::option::Option<u64>;
+}
+
+#[obce::definition(strict_abi)]
+pub trait ChangedTrait {
+    fn extension_method(&self, val: u64, bytes: Vec<u8>) -> Option<u64>;
+}
+
+mod a {
+    #[derive(scale::Encode, scale::Decode)]
+    pub struct Foo;
+}
+
+mod b {
+    #[derive(scale::Encode, scale::Decode)]
+    pub struct Foo;
+}
+
+#[obce::definition(strict_abi)]
+pub trait FirstPathTrait {
+    fn extension_method(&self, val: a::Foo);
+}
+
+#[obce::definition(strict_abi)]
+pub trait SecondPathTrait {
+    fn extension_method(&self, val: b::Foo);
+}
+
+#[obce::definition(strict_abi)]
+pub trait SaltedTrait {
+    #[obce(salt = 1)]
+    fn extension_method(&self, val: u32, bytes: Vec<u8>) -> Option<u64>;
+}
+
+#[obce::definition]
+pub trait AnotherTrait {
+    #[obce(id = 1)]
+    fn extension_method(&self, val: u32, bytes: Vec<u8>) -> Option<u64>;
+}
+
+#[obce::definition]
+pub trait AliasedTrait {
+    #[obce(id = 1, alias_id = 2)]
+    fn extension_method(&self, val: u32, bytes: Vec<u8>) -> Option<u64>;
+}
+
+fn main() {
+    assert!(<dyn Trait as ExtensionDescription>::STRICT_ABI);
+    assert_eq!(<dyn Trait as MethodDescription<0x3eae5bbc>>::SIGNATURE_HASH, 0xefa6cde8);
+    assert_const_eq::<{ id!(AnotherTrait::extension_method) }, 1>();
+
+    // Spelling the same types differently keeps the method identifier.
+    assert_eq!(
+        <dyn RespelledTrait as MethodDescription<0x3eae5bbc>>::SIGNATURE_HASH,
+        <dyn Trait as MethodDescription<0x3eae5bbc>>::SIGNATURE_HASH
+    );
+    assert_eq!(id!(RespelledTrait::extension_method), id!(Trait::extension_method));
+
+    // Changing the method signature changes the method identifier.
+    assert_ne!(
+        <dyn ChangedTrait as MethodDescription<0x3eae5bbc>>::SIGNATURE_HASH,
+        <dyn Trait as MethodDescription<0x3eae5bbc>>::SIGNATURE_HASH
+    );
+    assert_ne!(id!(ChangedTrait::extension_method), id!(Trait::extension_method));
+
+    // Types with the same name from different modules are distinguished.
+    assert_ne!(
+        <dyn FirstPathTrait as MethodDescription<0x3eae5bbc>>::SIGNATURE_HASH,
+        <dyn SecondPathTrait as MethodDescription<0x3eae5bbc>>::SIGNATURE_HASH
+    );
+
+    // Salt changes the method identifier, but not the signature hash.
+    assert_eq!(
+        <dyn SaltedTrait as MethodDescription<0x3eae5bbc>>::SIGNATURE_HASH,
+        <dyn Trait as MethodDescription<0x3eae5bbc>>::SIGNATURE_HASH
+    );
+    assert_eq!(id!(SaltedTrait::extension_method), id!(Trait::extension_method) ^ 1);
+
+    // Method aliases are a part of the ABI.
+    assert_ne!(
+        <dyn AliasedTrait as ExtensionDescription>::ABI_HASH,
+        <dyn AnotherTrait as ExtensionDescription>::ABI_HASH
+    );
+}
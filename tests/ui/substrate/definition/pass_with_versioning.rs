@@ -0,0 +1,23 @@
+#[obce::definition(id = 123, version = 2)]
+pub trait Trait {
+    #[obce(id = 1, alias_id = 100, alias_id = "old_method")]
+    fn method(&self, val: u32) -> u32;
+
+    #[obce(id = 2, since = 2)]
+    fn new_method(&self, val: u32) -> u32;
+
+    #[obce(id = 3, deprecated = "use `method` instead")]
+    fn deprecated_method(&self, val: u32) -> u32;
+}
+
+fn main() {
+    assert_eq!(<dyn Trait as obce::codegen::ExtensionDescription>::VERSION, 2);
+    assert_eq!(
+        <dyn Trait as obce::codegen::MethodDescription<{ obce::method_hash!(Trait::method) }>>::SINCE,
+        1
+    );
+    assert_eq!(
+        <dyn Trait as obce::codegen::MethodDescription<{ obce::method_hash!(Trait::new_method) }>>::SINCE,
+        2
+    );
+}
@@ -0,0 +1,11 @@
+#[obce::error]
+enum Error {
+    #[obce(ret_val = "100")]
+    One,
+    #[obce(unknown_status)]
+    Two(u32),
+    #[obce(unknown_status)]
+    Three(u32)
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use obce::substrate::{
+    frame_system::Config as SysConfig,
+    pallet_contracts::Config as ContractConfig,
+    sp_runtime::traits::StaticLookup,
+    ChainExtensionEnvironment,
+    ExtensionContext
+};
+
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn extension_method(&self, val: u32) -> u32;
+}
+
+#[obce::implementation]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    #[obce(max_depth = "4")]
+    fn extension_method(&self, val: u32) -> u32 {
+        val
+    }
+}
+
+fn main() {}
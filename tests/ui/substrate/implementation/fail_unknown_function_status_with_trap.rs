@@ -0,0 +1,28 @@
+use obce::substrate::{
+    frame_system::Config as SysConfig,
+    pallet_contracts::Config as ContractConfig,
+    sp_runtime::traits::StaticLookup,
+    ChainExtensionEnvironment,
+    ExtensionContext
+};
+
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn extension_method(&mut self, val: u64) -> u64;
+}
+
+#[obce::implementation(unknown_function_status, trap_unknown_functions)]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    fn extension_method(&mut self, val: u64) -> u64 {
+        val
+    }
+}
+
+fn main() {}
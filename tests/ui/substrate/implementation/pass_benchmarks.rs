@@ -0,0 +1,56 @@
+use obce::substrate::{
+    frame_support::dispatch::Weight,
+    frame_system::Config as SysConfig,
+    pallet_contracts::Config as ContractConfig,
+    sp_runtime::traits::StaticLookup,
+    sp_std::vec::Vec,
+    ChainExtensionEnvironment,
+    ExtensionContext
+};
+
+#[derive(Default)]
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn empty_method(&mut self);
+
+    fn sum(&mut self, items: Vec<u32>) -> u64;
+}
+
+pub struct ExtensionWeights;
+
+impl ChainExtensionDefinitionWeightInfo for ExtensionWeights {
+    fn empty_method() -> Weight {
+        Weight::from_parts(1_000, 0)
+    }
+
+    fn sum(n: u32) -> Weight {
+        Weight::from_parts(1_000 + 100 * n as u64, 0)
+    }
+}
+
+#[obce::implementation(benchmarks, weight_info = "ExtensionWeights")]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    #[obce(weight(info))]
+    fn empty_method(&mut self) {}
+
+    #[obce(
+        weight(info = "items.len() as u32"),
+        benchmark(
+            components(n = "0 .. 1000"),
+            setup = "let items: Vec<u32> = (0..n).collect();",
+            args = "items"
+        )
+    )]
+    fn sum(&mut self, items: Vec<u32>) -> u64 {
+        items.into_iter().map(u64::from).sum()
+    }
+}
+
+fn main() {}
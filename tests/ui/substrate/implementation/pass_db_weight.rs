@@ -0,0 +1,55 @@
+use obce::substrate::{
+    frame_support::dispatch::Weight,
+    frame_system::Config as SysConfig,
+    pallet_contracts::Config as ContractConfig,
+    sp_runtime::traits::StaticLookup,
+    sp_std::vec::Vec,
+    ChainExtensionEnvironment,
+    ExtensionContext
+};
+
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn lookup(&mut self, key: u32) -> u32;
+
+    fn batch_lookup(&mut self, keys: Vec<u32>) -> Vec<u32>;
+
+    fn update(&mut self, key: u32, value: u32);
+
+    fn bounded_lookup(&mut self, key: u32) -> u32;
+
+    fn batch_update(&mut self, keys: Vec<u32>);
+}
+
+#[obce::implementation]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    #[obce(weight(reads = 1))]
+    fn lookup(&mut self, key: u32) -> u32 {
+        key
+    }
+
+    #[obce(weight(reads = "keys.len()", base = "Weight::from_parts(10_000, 0)"))]
+    fn batch_lookup(&mut self, keys: Vec<u32>) -> Vec<u32> {
+        keys
+    }
+
+    #[obce(weight(reads = 1, writes = 1))]
+    fn update(&mut self, _key: u32, _value: u32) {}
+
+    #[obce(weight(reads = 2, proof_size = 128, actual = "Weight::from_parts(0, 0)"))]
+    fn bounded_lookup(&mut self, key: u32) -> u32 {
+        key
+    }
+
+    #[obce(weight(writes = "keys.len()", base = 10_000))]
+    fn batch_update(&mut self, keys: Vec<u32>) {}
+}
+
+fn main() {}
@@ -0,0 +1,43 @@
+use obce::substrate::{
+    frame_system::Config as SysConfig,
+    pallet_contracts::Config as ContractConfig,
+    sp_runtime::traits::StaticLookup,
+    sp_std::vec::Vec,
+    ChainExtensionEnvironment,
+    ExtensionContext
+};
+
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn bounded_method(&self, val: u32, account: [u8; 32]) -> u32;
+
+    fn unbounded_method(&self, bytes: Vec<u8>) -> u32;
+
+    fn depth_limited_method(&self, nested: Vec<Vec<u8>>) -> u32;
+}
+
+#[obce::implementation]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    fn bounded_method(&self, val: u32, _: [u8; 32]) -> u32 {
+        val
+    }
+
+    #[obce(max_input_len = 1024)]
+    fn unbounded_method(&self, bytes: Vec<u8>) -> u32 {
+        bytes.len() as u32
+    }
+
+    #[obce(max_input_len = 1024, max_depth = 4)]
+    fn depth_limited_method(&self, nested: Vec<Vec<u8>>) -> u32 {
+        nested.len() as u32
+    }
+}
+
+fn main() {}
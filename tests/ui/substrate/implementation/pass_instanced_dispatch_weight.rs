@@ -0,0 +1,39 @@
+mod test_instanced_pallet;
+
+use obce::substrate::{
+    frame_support::instances::Instance1,
+    frame_system::{Config as SysConfig, RawOrigin},
+    pallet_contracts::{
+        chain_extension::Ext,
+        Config as ContractConfig,
+    },
+    sp_runtime::traits::StaticLookup,
+    ChainExtensionEnvironment,
+    ExtensionContext
+};
+
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn extension_method(&mut self, val: u64);
+}
+
+#[obce::implementation]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig + crate::test_instanced_pallet::Config<Instance1>,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+    E: Ext<T = T>
+{
+    #[obce(weight(dispatch = "crate::test_instanced_pallet::Pallet::<T, Instance1>::test_method"))]
+    fn extension_method(&mut self, val: u64) {
+        crate::test_instanced_pallet::Pallet::<T, Instance1>::test_method(
+            RawOrigin::Signed(self.env.ext().address().clone()).into(),
+            val
+        ).unwrap();
+    }
+}
+
+fn main() {}
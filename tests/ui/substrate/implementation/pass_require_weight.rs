@@ -0,0 +1,35 @@
+use obce::substrate::{
+    frame_support::dispatch::Weight,
+    frame_system::Config as SysConfig,
+    pallet_contracts::Config as ContractConfig,
+    sp_runtime::traits::StaticLookup,
+    ChainExtensionEnvironment,
+    ExtensionContext
+};
+
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn first_method(&mut self, val: u64) -> u64;
+
+    fn second_method(&mut self);
+}
+
+#[obce::implementation(require_weight, base_weight = "Weight::from_parts(1_000, 0)")]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    #[obce(weight(expr = "Weight::from_parts(*val, 0)"))]
+    fn first_method(&mut self, val: u64) -> u64 {
+        val
+    }
+
+    #[obce(weight(reads = 1))]
+    fn second_method(&mut self) {}
+}
+
+fn main() {}
@@ -0,0 +1,52 @@
+use obce::substrate::{
+    frame_support::dispatch::Weight,
+    frame_system::Config as SysConfig,
+    pallet_contracts::Config as ContractConfig,
+    sp_runtime::{
+        traits::StaticLookup,
+        DispatchError,
+    },
+    sp_std::vec::Vec,
+    ChainExtensionEnvironment,
+    ExtensionContext
+};
+
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn sum(&mut self, items: Vec<u32>) -> Result<u64, DispatchError>;
+}
+
+#[obce::implementation]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    fn sum(&mut self, items: Vec<u32>) -> Result<u64, DispatchError> {
+        let mut sum = 0;
+
+        for item in items {
+            if self.remaining().any_lt(Weight::from_parts(2_000, 0)) {
+                break
+            }
+
+            self.charge(Weight::from_parts(2_000, 0))?;
+
+            if item == 0 {
+                // Zero items are cheaper to process.
+                self.refund(Weight::from_parts(1_000, 0))?;
+            }
+
+            sum += item as u64;
+        }
+
+        assert!(self.charged_so_far().all_gte(Weight::zero()));
+
+        Ok(sum)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,47 @@
+use obce::substrate::{
+    frame_support::dispatch::Weight,
+    frame_system::Config as SysConfig,
+    pallet_contracts::Config as ContractConfig,
+    sp_runtime::traits::StaticLookup,
+    sp_std::vec::Vec,
+    ChainExtensionEnvironment,
+    ExtensionContext
+};
+
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn store(&mut self, data: Vec<u8>);
+
+    fn load(&mut self) -> Vec<u8>;
+
+    fn echo(&mut self, data: Vec<u8>) -> Vec<u8>;
+}
+
+#[obce::implementation]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    #[obce(weight(per_input_byte = "Weight::from_parts(10, 1)"))]
+    fn store(&mut self, _data: Vec<u8>) {}
+
+    #[obce(weight(per_output_byte = "Weight::from_parts(20, 0)"))]
+    fn load(&mut self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    #[obce(weight(
+        expr = "Weight::from_parts(100, 0)",
+        per_input_byte = "Weight::from_parts(10, 1)",
+        per_output_byte = "Weight::from_parts(20, 0)"
+    ))]
+    fn echo(&mut self, data: Vec<u8>) -> Vec<u8> {
+        data
+    }
+}
+
+fn main() {}
@@ -0,0 +1,56 @@
+use obce::substrate::{
+    frame_support::dispatch::Weight,
+    frame_system::Config as SysConfig,
+    pallet_contracts::Config as ContractConfig,
+    sp_runtime::traits::StaticLookup,
+    ChainExtensionEnvironment,
+    ExtensionContext
+};
+
+pub struct ChainExtension;
+
+#[obce::error]
+pub enum Error {
+    NotFound,
+}
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    fn process(&mut self, items: u32) -> u32;
+
+    fn lookup(&mut self, key: u32) -> Result<u32, Error>;
+
+    fn pre_charged_process(&mut self, items: u32) -> u32;
+}
+
+#[obce::implementation]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    #[obce(weight(
+        max = "Weight::from_parts(1_000 * items as u64, 0)",
+        actual = "Weight::from_parts(1_000 * *result as u64, 0)"
+    ))]
+    fn process(&mut self, items: u32) -> u32 {
+        items / 2
+    }
+
+    #[obce(weight(
+        max = "Weight::from_parts(10_000, 0)",
+        actual = "if result.is_ok() { Weight::from_parts(10_000, 0) } else { Weight::from_parts(1_000, 0) }"
+    ))]
+    fn lookup(&mut self, _key: u32) -> Result<u32, Error> {
+        Err(Error::NotFound)
+    }
+
+    #[obce(weight(expr = "Weight::from_parts(10_000, 0)", pre_charge, actual = "Weight::from_parts(100, 0)"))]
+    fn pre_charged_process(&mut self, items: u32) -> u32 {
+        assert!(self.pre_charged().is_none());
+        items
+    }
+}
+
+fn main() {}
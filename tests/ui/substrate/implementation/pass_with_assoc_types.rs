@@ -0,0 +1,50 @@
+use obce::substrate::{
+    frame_system::Config as SysConfig,
+    pallet_contracts::Config as ContractConfig,
+    sp_runtime::traits::StaticLookup,
+    ChainExtensionEnvironment,
+    ExtensionContext,
+};
+
+pub struct ChainExtension;
+
+#[obce::definition]
+pub trait ChainExtensionDefinition {
+    type AccountId;
+
+    type BlockNumber;
+
+    const MAX_ACCOUNTS: u32 = 16;
+
+    fn account(&mut self, account: Self::AccountId) -> Option<Self::AccountId>;
+
+    fn is_genesis(&self, block: Self::BlockNumber) -> bool;
+
+    fn max_accounts(&self) -> u32;
+}
+
+#[obce::implementation]
+impl<'a, E, T, Env> ChainExtensionDefinition for ExtensionContext<'a, E, T, Env, ChainExtension>
+where
+    T: SysConfig + ContractConfig,
+    <<T as SysConfig>::Lookup as StaticLookup>::Source: From<<T as SysConfig>::AccountId>,
+    Env: ChainExtensionEnvironment<E, T>,
+{
+    type AccountId = T::AccountId;
+
+    type BlockNumber = T::BlockNumber;
+
+    fn account(&mut self, account: Self::AccountId) -> Option<Self::AccountId> {
+        Some(account)
+    }
+
+    fn is_genesis(&self, block: Self::BlockNumber) -> bool {
+        block == Default::default()
+    }
+
+    fn max_accounts(&self) -> u32 {
+        <dyn ChainExtensionDefinition>::MAX_ACCOUNTS
+    }
+}
+
+fn main() {}
@@ -19,6 +19,7 @@ pub struct ChainExtension;
 
 #[obce::definition]
 pub trait ChainExtensionDefinition {
+    #[obce(ret_val)]
     fn extension_method(&self) -> Result<(), Error>;
 }
 
@@ -0,0 +1,25 @@
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::OriginFor;
+
+    #[pallet::pallet]
+    pub struct Pallet<T, I = ()>(_);
+
+    #[pallet::config]
+    pub trait Config<I: 'static = ()>: frame_system::Config {}
+
+    #[pallet::call]
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_parts(*val, 0))]
+        pub fn test_method(
+            _origin: OriginFor<T>,
+            val: u64
+        ) -> DispatchResult {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,12 @@
+#[obce::definition(id = 123)]
+pub trait Assets {}
+
+#[obce::definition]
+pub trait Staking {}
+
+#[obce::definition(id = 123)]
+pub trait Governance {}
+
+obce::assert_unique_ids!(dyn Assets, dyn Staking, dyn Governance);
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[obce::definition]
+pub trait Assets {}
+
+obce::assert_unique_ids!(dyn Assets, u32);
+
+fn main() {}
@@ -0,0 +1,18 @@
+#[obce::definition]
+pub trait Assets {
+    fn balance_of(&self, account: u32) -> u128;
+
+    #[obce(alias_id = 1)]
+    fn total_supply(&self) -> u128;
+}
+
+mod nested {
+    #[obce::definition]
+    pub trait Staking {
+        fn stake(&mut self, amount: u128);
+    }
+}
+
+obce::assert_unique_ids!(dyn Assets, dyn nested::Staking);
+
+fn main() {}